@@ -0,0 +1,8 @@
+//! Reports built on top of a parsed [`Stylesheet`](crate::structs::Stylesheet)
+
+pub mod color_vision;
+pub mod contrast;
+pub mod important;
+pub mod palette;
+pub mod similar_colors;
+pub mod specificity;
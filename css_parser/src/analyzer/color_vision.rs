@@ -0,0 +1,142 @@
+use crate::color::vision::Deficiency;
+use crate::color::Color;
+use crate::structs::Stylesheet;
+
+use std::fmt;
+
+/// Pairs of palette colors which are easy to tell apart with normal vision,
+/// but look almost the same with a color vision deficiency.
+#[derive(PartialEq, Debug)]
+pub struct ColorVisionReport {
+    /// Smallest CIEDE2000 difference of colors which are distinct with normal vision
+    pub distinct: f32,
+    /// Largest CIEDE2000 difference of simulated colors which are confused
+    pub confused: f32,
+    /// Confused pairs grouped by deficiency, the most similar ones first
+    pub pairs: Vec<ConfusedPair>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ConfusedPair {
+    pub deficiency: Deficiency,
+    pub first: Color,
+    pub second: Color,
+    /// CIEDE2000 difference with normal vision
+    pub delta_e: f32,
+    /// CIEDE2000 difference of the simulated colors
+    pub simulated_delta_e: f32,
+}
+
+impl ColorVisionReport {
+    /// Compares every pair of distinct colors used in style rules
+    pub fn new(stylesheet: &Stylesheet, distinct: f32, confused: f32) -> ColorVisionReport {
+        let mut palette: Vec<&Color> = Vec::new();
+
+        for rule in stylesheet.style_rules() {
+            for declaration in &rule.declarations {
+                for color in declaration.value.colors() {
+                    if color.data.to_xyz().is_some() && !palette.iter().any(|c| c.same_color(color))
+                    {
+                        palette.push(color);
+                    }
+                }
+            }
+        }
+
+        let mut pairs = Vec::new();
+        for deficiency in Deficiency::ALL {
+            let simulated: Vec<Option<Color>> = palette
+                .iter()
+                .map(|color| color.simulate(deficiency))
+                .collect();
+            let start = pairs.len();
+
+            for i in 0..palette.len() {
+                for j in i + 1..palette.len() {
+                    let delta_e = match palette[i].delta_e(palette[j]) {
+                        Some(delta_e) if delta_e >= distinct => delta_e,
+                        _ => continue,
+                    };
+                    let simulated_delta_e = match (&simulated[i], &simulated[j]) {
+                        (Some(a), Some(b)) => a.delta_e(b),
+                        _ => None,
+                    };
+
+                    if let Some(simulated_delta_e) = simulated_delta_e.filter(|d| *d < confused) {
+                        pairs.push(ConfusedPair {
+                            deficiency,
+                            first: palette[i].clone(),
+                            second: palette[j].clone(),
+                            delta_e,
+                            simulated_delta_e,
+                        });
+                    }
+                }
+            }
+
+            pairs[start..].sort_by(|a, b| a.simulated_delta_e.total_cmp(&b.simulated_delta_e));
+        }
+
+        ColorVisionReport {
+            distinct,
+            confused,
+            pairs,
+        }
+    }
+}
+
+impl fmt::Display for ColorVisionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Colors confused with color vision deficiencies: {} pairs",
+            self.pairs.len()
+        )?;
+        for pair in &self.pairs {
+            write!(
+                f,
+                "\n    {}: {} and {} (ΔE {:.2} → {:.2})",
+                pair.deficiency, pair.first, pair.second, pair.delta_e, pair.simulated_delta_e
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse;
+
+    #[test]
+    fn report() {
+        let (stylesheet, _) = parse(
+            ".error { color: #b33 } .success { color: #663 } .link { color: #36c; border-color: #3366cc } \
+             .note { background: #693 }",
+        );
+        let report = ColorVisionReport::new(&stylesheet, 10., 8.);
+
+        let pairs: Vec<(Deficiency, &str, &str)> = report
+            .pairs
+            .iter()
+            .map(|pair| {
+                (
+                    pair.deficiency,
+                    pair.first.original.as_str(),
+                    pair.second.original.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (Deficiency::Protanopia, "#b33", "#663"),
+                (Deficiency::Deuteranopia, "#b33", "#663"),
+            ]
+        );
+        assert!(format!("{}", report)
+            .starts_with("Colors confused with color vision deficiencies: 2 pairs\n    protanopia: #b33 and #663 (ΔE 36.89 → 7.24)"));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::color::contrast::{WCAG_AA, WCAG_AAA};
+use crate::color::{Color, ColorData};
+use crate::span::Span;
+use crate::structs::{Declaration, Stylesheet};
+
+use std::fmt;
+
+/// Contrast of `color` and `background-color` pairs, for accessibility checks.
+///
+/// Declarations of rules with identical selectors are combined, later ones
+/// win, so `a { color: #777 } a { background: #fff }` is one pair. A
+/// `currentColor` background is the `color` of the same selector.
+#[derive(PartialEq, Debug)]
+pub struct ContrastReport {
+    /// Color translucent backgrounds are drawn over, usually the page background
+    pub base: Color,
+    /// Pairs in the order of their selectors in the stylesheet
+    pub pairs: Vec<ContrastPair>,
+    /// Pairs with `color: currentColor`, which inherits the color of the
+    /// parent element and can't be resolved from the stylesheet alone
+    pub unresolved: Vec<UnresolvedPair>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ContrastPair {
+    pub selector: String,
+    pub foreground: Color,
+    pub background: Color,
+    /// WCAG 2.1 contrast ratio of the colors composited over the base
+    pub ratio: f32,
+    /// APCA lightness contrast of the colors composited over the base
+    pub apca: f32,
+    /// Span of the `color` value
+    pub span: Span,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct UnresolvedPair {
+    pub selector: String,
+    pub foreground: Color,
+    pub background: Color,
+    /// Span of the `color` value
+    pub span: Span,
+}
+
+impl ContrastReport {
+    pub fn new(stylesheet: &Stylesheet, base: &Color) -> ContrastReport {
+        // Foreground and background declarations of each distinct selector
+        let mut selectors: Vec<(String, Option<&Declaration>, Option<&Declaration>)> = Vec::new();
+
+        for rule in stylesheet.style_rules() {
+            let selector = rule.selector_text();
+            let index = match selectors.iter().position(|(s, _, _)| *s == selector) {
+                Some(index) => index,
+                None => {
+                    selectors.push((selector, None, None));
+                    selectors.len() - 1
+                }
+            };
+
+            for declaration in &rule.declarations {
+                if color(declaration).is_none() {
+                    continue;
+                }
+                match declaration.property.as_str() {
+                    "color" => selectors[index].1 = Some(declaration),
+                    "background" | "background-color" => selectors[index].2 = Some(declaration),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut pairs = Vec::new();
+        let mut unresolved = Vec::new();
+
+        for (selector, foreground, background) in selectors {
+            let (foreground, background) = match (foreground, background) {
+                (Some(foreground), Some(background)) => (foreground, background),
+                _ => continue,
+            };
+            let span = foreground.value_span;
+            let (foreground, background) = match (color(foreground), color(background)) {
+                (Some(foreground), Some(background)) => (foreground, background),
+                _ => continue,
+            };
+
+            if foreground.data == ColorData::CURRENTCOLOR {
+                unresolved.push(UnresolvedPair {
+                    selector,
+                    foreground,
+                    background,
+                    span,
+                });
+                continue;
+            }
+            let background = background.resolve_current_color(&foreground);
+
+            if let Some(pair) = ContrastPair::new(selector, foreground, background, span, base) {
+                pairs.push(pair);
+            }
+        }
+
+        ContrastReport {
+            base: base.clone(),
+            pairs,
+            unresolved,
+        }
+    }
+
+    /// Pairs below the WCAG AA contrast of normal text
+    pub fn aa_failures(&self) -> Vec<&ContrastPair> {
+        self.pairs
+            .iter()
+            .filter(|pair| pair.ratio < WCAG_AA)
+            .collect()
+    }
+
+    /// Pairs below the WCAG AAA contrast of normal text, including AA failures
+    pub fn aaa_failures(&self) -> Vec<&ContrastPair> {
+        self.pairs
+            .iter()
+            .filter(|pair| pair.ratio < WCAG_AAA)
+            .collect()
+    }
+}
+
+impl ContrastPair {
+    fn new(
+        selector: String,
+        foreground: Color,
+        background: Color,
+        span: Span,
+        base: &Color,
+    ) -> Option<ContrastPair> {
+        let opaque_background = background.composite_over(base)?;
+        let opaque_foreground = foreground.composite_over(&opaque_background)?;
+
+        Some(ContrastPair {
+            selector,
+            ratio: opaque_foreground.contrast_ratio(&opaque_background)?,
+            apca: opaque_foreground.apca_contrast(&opaque_background)?,
+            foreground,
+            background,
+            span,
+        })
+    }
+}
+
+/// Valid color or `currentColor` of a declaration, the first color of a
+/// `background` shorthand
+fn color(declaration: &Declaration) -> Option<Color> {
+    declaration
+        .value
+        .colors()
+        .into_iter()
+        .next()
+        .filter(|color| color.data.to_xyz().is_some() || color.data == ColorData::CURRENTCOLOR)
+        .cloned()
+}
+
+impl fmt::Display for ContrastReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Contrast of {} color pairs over {}:",
+            self.pairs.len(),
+            self.base
+        )?;
+        for pair in self.aaa_failures() {
+            let level = match pair.ratio < WCAG_AA {
+                true => "AA",
+                false => "AAA",
+            };
+            write!(
+                f,
+                "\n    fails {} {:.2}:1 Lc {:.1} {} on {} {} ({})",
+                level,
+                pair.ratio,
+                pair.apca,
+                pair.foreground,
+                pair.background,
+                pair.selector,
+                pair.span
+            )?;
+        }
+        for pair in &self.unresolved {
+            write!(
+                f,
+                "\n    unresolved {} on {} {} ({})",
+                pair.foreground, pair.background, pair.selector, pair.span
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse;
+
+    #[test]
+    fn report() {
+        let (stylesheet, _) = parse(
+            ".a { color: #777; background-color: #fff } \
+             .b { color: #000; background: #fff url(a.png) } \
+             .c { color: #595959 } .c { background-color: white } \
+             .d { color: rgb(0 0 0 / 50%); background-color: transparent } \
+             .e { color: #000 } \
+             .f { color: #000; background-color: rgb(0 0 0 / 0.6) } \
+             .g { color: #777; background-color: currentColor } .h { color: currentColor; background: #fff }",
+        );
+        let report = ContrastReport::new(&stylesheet, &Color::new("#fff"));
+
+        let pairs: Vec<(&str, String)> = report
+            .pairs
+            .iter()
+            .map(|pair| (pair.selector.as_str(), format!("{:.2}", pair.ratio)))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (".a", "4.48".to_string()),
+                (".b", "21.00".to_string()),
+                (".c", "7.00".to_string()),
+                (".d", "3.98".to_string()),
+                (".f", "3.66".to_string()),
+                (".g", "1.00".to_string()),
+            ]
+        );
+        assert_eq!(
+            report
+                .unresolved
+                .iter()
+                .map(|pair| (pair.selector.as_str(), pair.foreground.to_string()))
+                .collect::<Vec<_>>(),
+            vec![(".h", "currentColor".to_string())]
+        );
+        assert_eq!(report.aa_failures().len(), 4);
+        assert_eq!(report.aaa_failures().len(), 4);
+        assert_eq!(
+            format!("{}", report),
+            "Contrast of 6 color pairs over #fff:\
+             \n    fails AA 4.48:1 Lc 71.1 #777 on #fff .a (1:13)\
+             \n    fails AA 3.98:1 Lc 67.1 rgb(0 0 0 / 50%) on transparent .d (1:157)\
+             \n    fails AA 3.66:1 Lc 25.3 #000 on rgb(0 0 0 / 0.6) .f (1:238)\
+             \n    fails AA 1.00:1 Lc 0.0 #777 on #777 .g (1:293)\
+             \n    unresolved currentColor on #fff .h (1:344)"
+        );
+    }
+
+    #[test]
+    fn translucent_colors() {
+        let (stylesheet, _) =
+            parse("a { color: rgb(0 0 0 / 50%); background-color: rgb(255 255 255 / 0.5) }");
+
+        let over_white = ContrastReport::new(&stylesheet, &Color::new("white"));
+        let over_black = ContrastReport::new(&stylesheet, &Color::new("black"));
+
+        assert!(
+            (over_white.pairs[0].ratio - 3.98).abs() < 0.01,
+            "{}",
+            over_white.pairs[0].ratio
+        );
+        assert!(
+            (over_black.pairs[0].ratio - 2.62).abs() < 0.01,
+            "{}",
+            over_black.pairs[0].ratio
+        );
+    }
+}
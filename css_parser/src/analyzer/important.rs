@@ -0,0 +1,105 @@
+use crate::structs::Stylesheet;
+
+use std::fmt;
+
+/// `!important` usage across one or more stylesheets.
+#[derive(PartialEq, Default, Debug)]
+pub struct ImportantReport {
+    /// Number of `!important` declarations per property, most used first
+    pub per_property: Vec<(String, usize)>,
+    /// Number of `!important` declarations per file, in the order files were added
+    pub per_file: Vec<(String, usize)>,
+}
+
+impl ImportantReport {
+    pub fn new(file: &str, stylesheet: &Stylesheet) -> ImportantReport {
+        let mut report = ImportantReport::default();
+        report.add(file, stylesheet);
+        report
+    }
+
+    /// Counts `!important` declarations in style rules of `stylesheet`
+    pub fn add(&mut self, file: &str, stylesheet: &Stylesheet) {
+        let mut count = 0;
+
+        for rule in stylesheet.style_rules() {
+            for declaration in rule.declarations.iter().filter(|d| d.important) {
+                count += 1;
+
+                match self
+                    .per_property
+                    .iter_mut()
+                    .find(|(property, _)| *property == declaration.property)
+                {
+                    Some((_, count)) => *count += 1,
+                    None => self.per_property.push((declaration.property.clone(), 1)),
+                }
+            }
+        }
+
+        self.per_property
+            .sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        self.per_file.push((file.to_string(), count));
+    }
+
+    /// Number of `!important` declarations in all files
+    pub fn total(&self) -> usize {
+        self.per_file.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl fmt::Display for ImportantReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "!important declarations: {}", self.total())?;
+        write!(f, "\nBy file:")?;
+        for (file, count) in &self.per_file {
+            write!(f, "\n    {}: {}", file, count)?;
+        }
+        write!(f, "\nBy property:")?;
+        for (property, count) in &self.per_property {
+            write!(f, "\n    {}: {}", property, count)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse;
+
+    #[test]
+    fn report() {
+        let mut report = ImportantReport::new(
+            "a.css",
+            &parse(
+                "a { color: red !important; width: 1px !important } \
+                 @media print { b { color: blue !important; margin: 0 } }",
+            )
+            .0,
+        );
+        report.add("b.css", &parse("c { width: 2px !important }").0);
+        report.add("c.css", &parse("d { width: 3px }").0);
+
+        assert_eq!(
+            report.per_property,
+            vec![("color".to_string(), 2), ("width".to_string(), 2)]
+        );
+        assert_eq!(
+            report.per_file,
+            vec![
+                ("a.css".to_string(), 3),
+                ("b.css".to_string(), 1),
+                ("c.css".to_string(), 0)
+            ]
+        );
+        assert_eq!(report.total(), 4);
+        assert_eq!(
+            format!("{}", report),
+            "!important declarations: 4\nBy file:\n    a.css: 3\n    b.css: 1\n    c.css: 0\n\
+             By property:\n    color: 2\n    width: 2"
+        );
+    }
+}
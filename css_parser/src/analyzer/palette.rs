@@ -0,0 +1,148 @@
+use crate::color::Color;
+use crate::span::Span;
+use crate::structs::Stylesheet;
+
+use std::fmt;
+
+/// Colors written as literals which are close to a color of the design palette,
+/// e.g. `#fe0000` when `--red-500: #f00` is declared, and should use the token.
+#[derive(PartialEq, Debug)]
+pub struct PaletteReport {
+    /// Largest CIEDE2000 difference between a color and the token it snaps to
+    pub threshold: f32,
+    /// Custom properties whose value is a color, in declaration order
+    pub tokens: Vec<PaletteToken>,
+    /// Suggestions in the order of the declarations
+    pub suggestions: Vec<PaletteSuggestion>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct PaletteToken {
+    /// Custom property name, e.g. `--red-500`
+    pub name: String,
+    pub color: Color,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct PaletteSuggestion {
+    pub color: Color,
+    /// Custom property name of the closest palette color
+    pub token: String,
+    /// CIEDE2000 difference to the palette color
+    pub delta_e: f32,
+    pub selector: String,
+    pub property: String,
+    /// Span of the declaration value
+    pub span: Span,
+}
+
+impl PaletteReport {
+    /// Snaps colors of all style rules to the palette made of custom properties
+    /// with a color value. As with `var()` substitution every custom property is
+    /// global and the last declaration wins.
+    pub fn new(stylesheet: &Stylesheet, threshold: f32) -> PaletteReport {
+        let mut tokens: Vec<PaletteToken> = Vec::new();
+
+        for rule in stylesheet.style_rules() {
+            for declaration in &rule.declarations {
+                if !declaration.property.starts_with("--") {
+                    continue;
+                }
+                tokens.retain(|token| token.name != declaration.property);
+
+                let color = Color::new(declaration.raw_value.trim());
+                if color.data.to_xyz().is_some() {
+                    tokens.push(PaletteToken {
+                        name: declaration.property.clone(),
+                        color,
+                    });
+                }
+            }
+        }
+
+        let palette: Vec<Color> = tokens.iter().map(|token| token.color.clone()).collect();
+        let mut suggestions = Vec::new();
+
+        for rule in stylesheet.style_rules() {
+            for declaration in &rule.declarations {
+                for color in declaration.value.colors() {
+                    match color.snap_to_palette(&palette) {
+                        Some((index, delta_e)) if delta_e < threshold => {
+                            suggestions.push(PaletteSuggestion {
+                                color: color.clone(),
+                                token: tokens[index].name.clone(),
+                                delta_e,
+                                selector: rule.selector_text(),
+                                property: declaration.property.clone(),
+                                span: declaration.value_span,
+                            })
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        PaletteReport {
+            threshold,
+            tokens,
+            suggestions,
+        }
+    }
+}
+
+impl fmt::Display for PaletteReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Palette suggestions (ΔE < {}, {} tokens): {}",
+            self.threshold,
+            self.tokens.len(),
+            self.suggestions.len()
+        )?;
+        for suggestion in &self.suggestions {
+            write!(
+                f,
+                "\n    {} is {:.1} ΔE from token {}, use it: {} {} ({})",
+                suggestion.color,
+                suggestion.delta_e,
+                suggestion.token,
+                suggestion.selector,
+                suggestion.property,
+                suggestion.span
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse;
+
+    #[test]
+    fn report() {
+        let (stylesheet, _) = parse(
+            ":root { --red-500: #f00; --blue-500: #3b82f6; --space: 4px; --blue-500: #2563eb } \
+             .a { color: #fe0000 } .b { background-color: #2564eb; border-color: #3b82f6 } \
+             .c { color: var(--red-500); background: #0f0 }",
+        );
+        let report = PaletteReport::new(&stylesheet, 2.);
+
+        let tokens: Vec<&str> = report
+            .tokens
+            .iter()
+            .map(|token| token.name.as_str())
+            .collect();
+        assert_eq!(tokens, vec!["--red-500", "--blue-500"]);
+        assert_eq!(
+            format!("{}", report),
+            "Palette suggestions (ΔE < 2, 2 tokens): 2\
+             \n    #fe0000 is 0.2 ΔE from token --red-500, use it: .a color (1:95)\
+             \n    #2564eb is 0.3 ΔE from token --blue-500, use it: .b background-color (1:128)"
+        );
+    }
+}
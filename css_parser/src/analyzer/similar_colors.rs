@@ -0,0 +1,220 @@
+use crate::color::Color;
+use crate::span::Span;
+use crate::structs::Stylesheet;
+
+use std::cmp::Reverse;
+use std::fmt;
+
+/// Groups of colors which look almost the same, e.g. `#333`, `#343434` and
+/// `rgb(52, 51, 51)`, which could be replaced with one of them.
+#[derive(PartialEq, Debug)]
+pub struct SimilarColorsReport {
+    /// Largest CIEDE2000 difference between colors of a cluster and their neighbours
+    pub threshold: f32,
+    /// Clusters with at least two variants, the ones with most variants first
+    pub clusters: Vec<ColorCluster>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ColorCluster {
+    /// Variants of the color, the most used one first. It is the suggested
+    /// replacement of the others.
+    pub variants: Vec<ColorVariant>,
+}
+
+/// Color as written in the stylesheet and the declarations using it
+#[derive(PartialEq, Debug)]
+pub struct ColorVariant {
+    pub color: Color,
+    /// CIEDE2000 difference to the first variant of the cluster
+    pub delta_e: f32,
+    pub usages: Vec<ColorUsage>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ColorUsage {
+    pub selector: String,
+    pub property: String,
+    /// Span of the declaration value
+    pub span: Span,
+}
+
+impl SimilarColorsReport {
+    /// Clusters colors of all style rules.
+    ///
+    /// Colors are in one cluster if there is a chain of colors between them where
+    /// each step differs by less than `threshold`, and they have the same alpha.
+    pub fn new(stylesheet: &Stylesheet, threshold: f32) -> SimilarColorsReport {
+        let mut variants: Vec<ColorVariant> = Vec::new();
+
+        for rule in stylesheet.style_rules() {
+            for declaration in &rule.declarations {
+                for color in declaration.value.colors() {
+                    if color.data.to_xyz().is_none() {
+                        continue;
+                    }
+                    let usage = ColorUsage {
+                        selector: rule.selector_text(),
+                        property: declaration.property.clone(),
+                        span: declaration.value_span,
+                    };
+
+                    match variants
+                        .iter_mut()
+                        .find(|variant| variant.color.original == color.original)
+                    {
+                        Some(variant) => variant.usages.push(usage),
+                        None => variants.push(ColorVariant {
+                            color: color.clone(),
+                            delta_e: 0.,
+                            usages: vec![usage],
+                        }),
+                    }
+                }
+            }
+        }
+
+        // Single-linkage clustering, `cluster[i]` is the cluster of `variants[i]`
+        let mut cluster: Vec<usize> = (0..variants.len()).collect();
+        for i in 0..variants.len() {
+            for j in i + 1..variants.len() {
+                if cluster[i] != cluster[j]
+                    && similar(&variants[i].color, &variants[j].color, threshold)
+                {
+                    let (from, to) = (cluster[j], cluster[i]);
+                    cluster
+                        .iter_mut()
+                        .filter(|c| **c == from)
+                        .for_each(|c| *c = to);
+                }
+            }
+        }
+
+        let mut clusters: Vec<ColorCluster> = Vec::new();
+        let mut indices: Vec<usize> = Vec::new();
+        for (variant, index) in variants.into_iter().zip(cluster) {
+            match indices.iter().position(|i| *i == index) {
+                Some(position) => clusters[position].variants.push(variant),
+                None => {
+                    indices.push(index);
+                    clusters.push(ColorCluster {
+                        variants: vec![variant],
+                    });
+                }
+            }
+        }
+
+        clusters.retain(|cluster| cluster.variants.len() > 1);
+        for cluster in &mut clusters {
+            cluster
+                .variants
+                .sort_by_key(|variant| Reverse(variant.usages.len()));
+            let representative = cluster.variants[0].color.clone();
+
+            for variant in &mut cluster.variants {
+                variant.delta_e = representative.delta_e(&variant.color).unwrap_or_default();
+            }
+        }
+        clusters.sort_by_key(|cluster| Reverse(cluster.variants.len()));
+
+        SimilarColorsReport {
+            threshold,
+            clusters,
+        }
+    }
+}
+
+fn similar(a: &Color, b: &Color, threshold: f32) -> bool {
+    let alpha = |color: &Color| color.data.to_xyz().map(|(_, alpha)| alpha);
+
+    (alpha(a).unwrap_or(1.) - alpha(b).unwrap_or(1.)).abs() < 0.01
+        && a.delta_e(b).is_some_and(|delta_e| delta_e < threshold)
+}
+
+impl fmt::Display for SimilarColorsReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Similar colors (ΔE < {}): {} groups",
+            self.threshold,
+            self.clusters.len()
+        )?;
+        for cluster in &self.clusters {
+            write!(f, "\n    use {}:", cluster.variants[0].color)?;
+
+            for variant in &cluster.variants {
+                let usages: Vec<String> = variant
+                    .usages
+                    .iter()
+                    .map(|usage| format!("{} {} ({})", usage.selector, usage.property, usage.span))
+                    .collect();
+
+                match variant.delta_e == 0. {
+                    true => write!(f, "\n        {}: ", variant.color)?,
+                    false => write!(
+                        f,
+                        "\n        {} (ΔE {:.2}): ",
+                        variant.color, variant.delta_e
+                    )?,
+                }
+                write!(f, "{}", usages.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse;
+
+    #[test]
+    fn report() {
+        let (stylesheet, _) = parse(
+            ".a { color: #333 } .b { border-color: #343434 } .c { color: #333; background: rgb(52, 51, 51) } \
+             .d { color: red; background-color: #fe0000 } .e { color: #fff; outline: 1px solid rgba(51, 51, 51, 0.5) }",
+        );
+        let report = SimilarColorsReport::new(&stylesheet, 2.);
+
+        let clusters: Vec<Vec<&str>> = report
+            .clusters
+            .iter()
+            .map(|cluster| {
+                cluster
+                    .variants
+                    .iter()
+                    .map(|variant| variant.color.original.as_str())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            clusters,
+            vec![
+                vec!["#333", "#343434", "rgb(52, 51, 51)"],
+                vec!["red", "#fe0000"]
+            ]
+        );
+        assert_eq!(
+            format!("{}", report),
+            "Similar colors (ΔE < 2): 2 groups\
+             \n    use #333:\
+             \n        #333: .a color (1:13), .c color (1:61)\
+             \n        #343434 (ΔE 0.32): .b border-color (1:39)\
+             \n        rgb(52, 51, 51) (ΔE 0.69): .c background (1:79)\
+             \n    use red:\
+             \n        red: .d color (1:109)\
+             \n        #fe0000 (ΔE 0.21): .d background-color (1:132)"
+        );
+    }
+
+    #[test]
+    fn chained_colors() {
+        let (stylesheet, _) = parse("a { color: #333 } b { color: #383838 } c { color: #3d3d3d }");
+
+        assert_eq!(SimilarColorsReport::new(&stylesheet, 2.).clusters.len(), 1);
+        assert_eq!(SimilarColorsReport::new(&stylesheet, 1.).clusters.len(), 0);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::selector::Specificity;
+use crate::span::Span;
+use crate::structs::Stylesheet;
+
+use std::cmp::Reverse;
+use std::fmt;
+
+/// Specificity of every selector in a stylesheet.
+#[derive(PartialEq, Debug)]
+pub struct SpecificityReport {
+    /// Number of selectors with each specificity, lowest specificity first
+    pub distribution: Vec<(Specificity, usize)>,
+    /// Most specific selectors, highest first. Equal ones keep the source order.
+    pub highest: Vec<SelectorSpecificity>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct SelectorSpecificity {
+    pub selector: String,
+    pub specificity: Specificity,
+    pub span: Span,
+}
+
+impl SpecificityReport {
+    /// Collects selectors of all style rules, `limit` is the length of `highest`
+    pub fn new(stylesheet: &Stylesheet, limit: usize) -> SpecificityReport {
+        let mut selectors: Vec<SelectorSpecificity> = stylesheet
+            .style_rules()
+            .iter()
+            .flat_map(|rule| rule.selectors.iter())
+            .map(|selector| SelectorSpecificity {
+                selector: format!("{}", selector),
+                specificity: selector.specificity(),
+                span: selector.span,
+            })
+            .collect();
+
+        let mut distribution: Vec<(Specificity, usize)> = Vec::new();
+        for selector in &selectors {
+            match distribution
+                .iter_mut()
+                .find(|(specificity, _)| *specificity == selector.specificity)
+            {
+                Some((_, count)) => *count += 1,
+                None => distribution.push((selector.specificity, 1)),
+            }
+        }
+        distribution.sort();
+
+        selectors.sort_by_key(|selector| Reverse(selector.specificity));
+        selectors.truncate(limit);
+
+        SpecificityReport {
+            distribution,
+            highest: selectors,
+        }
+    }
+
+    /// Number of analyzed selectors
+    pub fn total(&self) -> usize {
+        self.distribution.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl fmt::Display for SpecificityReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Specificity of {} selectors:", self.total())?;
+        for ((a, b, c), count) in &self.distribution {
+            writeln!(f, "    {},{},{}: {}", a, b, c, count)?;
+        }
+
+        write!(f, "Highest specificity:")?;
+        for selector in &self.highest {
+            let (a, b, c) = selector.specificity;
+            write!(
+                f,
+                "\n    {},{},{} {} ({})",
+                a, b, c, selector.selector, selector.span
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse;
+
+    #[test]
+    fn selector_specificity() {
+        let tests = [
+            ("*", (0, 0, 0)),
+            ("li", (0, 0, 1)),
+            ("ul li", (0, 0, 2)),
+            ("ul ol+li", (0, 0, 3)),
+            ("h1 + *[rel=up]", (0, 1, 1)),
+            ("ul ol li.red", (0, 1, 3)),
+            ("li.red.level", (0, 2, 1)),
+            ("#x34y", (1, 0, 0)),
+            ("a:hover::before", (0, 1, 2)),
+            ("p:after", (0, 0, 2)),
+            (":is(em, #foo)", (1, 0, 0)),
+            (":not(.a, div#b)", (1, 0, 1)),
+            ("a:where(#x, .y)", (0, 0, 1)),
+            ("div:has(> img, .x)", (0, 1, 1)),
+            ("li:nth-child(2n+1)", (0, 1, 1)),
+            ("li:nth-child(2n+1 of #a, .b)", (1, 1, 1)),
+            ("p:lang(en)", (0, 1, 1)),
+        ];
+
+        for (css, expected) in tests {
+            let (stylesheet, _) = parse(&format!("{} {{}}", css));
+
+            assert_eq!(
+                stylesheet.style_rules()[0].selectors[0].specificity(),
+                expected,
+                "specificity of {}",
+                css
+            );
+        }
+    }
+
+    #[test]
+    fn report() {
+        let (stylesheet, _) = parse(
+            "a, .b { color: red; } @media print { #c .d { color: red; } } \
+             e.f, g { color: red; } @keyframes k { from { color: red; } }",
+        );
+        let report = SpecificityReport::new(&stylesheet, 2);
+
+        assert_eq!(
+            report.distribution,
+            vec![
+                ((0, 0, 1), 2),
+                ((0, 1, 0), 1),
+                ((0, 1, 1), 1),
+                ((1, 1, 0), 1)
+            ]
+        );
+        assert_eq!(report.total(), 5);
+        assert_eq!(
+            report
+                .highest
+                .iter()
+                .map(|s| s.selector.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["#c .d", "e.f"]
+        );
+        assert_eq!(
+            format!("{}", report),
+            "Specificity of 5 selectors:\n    0,0,1: 2\n    0,1,0: 1\n    0,1,1: 1\n    1,1,0: 1\n\
+             Highest specificity:\n    1,1,0 #c .d (1:38)\n    0,1,1 e.f (1:62)"
+        );
+    }
+}
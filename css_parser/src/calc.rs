@@ -0,0 +1,730 @@
+use crate::length::LengthContext;
+use crate::structs::{Unit, UnitKind, Value};
+
+use std::fmt;
+
+/// Math function or one of its operands, e.g. `calc(100% - 2 * var(--gutter))`
+#[derive(PartialEq, Clone, Debug)]
+pub enum Calc {
+    /// Number, percentage, dimension, constant like `pi`, or `var()`
+    Value(Box<Value>),
+    Operation {
+        left: Box<Calc>,
+        operator: Operator,
+        right: Box<Calc>,
+    },
+    Function {
+        function: MathFunction,
+        arguments: Vec<Calc>,
+    },
+}
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum MathFunction {
+    Calc,
+    Min,
+    Max,
+    Clamp,
+}
+/// Type of a math expression
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum CalcType {
+    Number,
+    Length,
+    Angle,
+    Time,
+    Frequency,
+    Resolution,
+    Flex,
+    Percentage,
+    /// Type of `var()`, which is only known after substitution
+    Unknown,
+}
+
+impl MathFunction {
+    /// Math function of a lowercased function name
+    pub fn new(name: &str) -> Option<MathFunction> {
+        match name {
+            "calc" => Some(MathFunction::Calc),
+            "min" => Some(MathFunction::Min),
+            "max" => Some(MathFunction::Max),
+            "clamp" => Some(MathFunction::Clamp),
+            _ => None,
+        }
+    }
+}
+
+impl Calc {
+    pub fn value(value: Value) -> Calc {
+        Calc::Value(Box::new(value))
+    }
+
+    pub fn operation(left: Calc, operator: Operator, right: Calc) -> Calc {
+        Calc::Operation {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        }
+    }
+
+    /// Checks that operands can be combined, e.g. `1px + 1deg` is an error
+    pub fn calc_type(&self) -> Result<CalcType, String> {
+        match self {
+            Calc::Value(value) => value_type(value),
+            Calc::Operation {
+                left,
+                operator,
+                right,
+            } => {
+                let (left, right) = (left.calc_type()?, right.calc_type()?);
+
+                match operator {
+                    Operator::Add | Operator::Subtract => add_types(left, right),
+                    Operator::Multiply => match (left, right) {
+                        (CalcType::Number, other) | (other, CalcType::Number) => Ok(other),
+                        (CalcType::Unknown, _) | (_, CalcType::Unknown) => Ok(CalcType::Unknown),
+                        _ => Err(format!("cannot multiply {} by {}", left, right)),
+                    },
+                    Operator::Divide => match (left, right) {
+                        (_, CalcType::Number) => Ok(left),
+                        (_, CalcType::Unknown) => Ok(CalcType::Unknown),
+                        _ => Err(format!("cannot divide {} by {}", left, right)),
+                    },
+                }
+            }
+            Calc::Function { arguments, .. } => {
+                let mut result = CalcType::Unknown;
+
+                for argument in arguments {
+                    result = add_types(result, argument.calc_type()?)?;
+                }
+
+                Ok(result)
+            }
+        }
+    }
+
+    /// Expression with constant parts computed, following the simplification
+    /// of CSS Values 4: terms of a sum with compatible units are added up,
+    /// products with numbers are computed, and min(), max() and clamp()
+    /// are resolved when all their arguments have the same unit.
+    pub fn simplify(&self) -> Calc {
+        match (self, self.simplify_node()) {
+            (
+                Calc::Function { .. },
+                Calc::Function {
+                    function,
+                    arguments,
+                },
+            ) => Calc::Function {
+                function,
+                arguments,
+            },
+            (Calc::Function { .. }, simplified) => Calc::Function {
+                function: MathFunction::Calc,
+                arguments: vec![simplified],
+            },
+            (_, simplified) => simplified,
+        }
+    }
+
+    fn simplify_node(&self) -> Calc {
+        match self {
+            Calc::Value(_) => self.clone(),
+            Calc::Operation {
+                operator: Operator::Add | Operator::Subtract,
+                ..
+            } => {
+                let mut terms = Vec::new();
+                self.collect_terms(1.0, &mut terms);
+
+                simplify_sum(terms)
+            }
+            Calc::Operation {
+                left,
+                operator,
+                right,
+            } => {
+                let (left, right) = (left.simplify_node(), right.simplify_node());
+
+                match (numeric(&left), numeric(&right), operator) {
+                    (Some((a, None)), Some((b, unit)), Operator::Multiply)
+                    | (Some((b, unit)), Some((a, None)), Operator::Multiply) => leaf(a * b, unit),
+                    (Some((a, unit)), Some((b, None)), Operator::Divide) if b != 0.0 => {
+                        leaf(a / b, unit)
+                    }
+                    _ => Calc::operation(left, *operator, right),
+                }
+            }
+            Calc::Function {
+                function: MathFunction::Calc,
+                arguments,
+            } => match arguments.as_slice() {
+                [argument] => argument.simplify_node(),
+                _ => self.clone(),
+            },
+            Calc::Function {
+                function,
+                arguments,
+            } => {
+                let arguments: Vec<Calc> = arguments.iter().map(Calc::simplify_node).collect();
+                let values: Option<Vec<(f32, Option<Unit>)>> =
+                    arguments.iter().map(numeric).collect();
+
+                let values = match values {
+                    Some(values) if values.windows(2).all(|w| w[0].1 == w[1].1) => values,
+                    _ => {
+                        return Calc::Function {
+                            function: *function,
+                            arguments,
+                        }
+                    }
+                };
+                let numbers = values.iter().map(|(value, _)| *value);
+
+                let (result, unit) = match (function, values.as_slice()) {
+                    (MathFunction::Min, [(_, unit), ..]) => {
+                        (numbers.fold(f32::INFINITY, f32::min), unit)
+                    }
+                    (MathFunction::Max, [(_, unit), ..]) => {
+                        (numbers.fold(f32::NEG_INFINITY, f32::max), unit)
+                    }
+                    (MathFunction::Clamp, [(min, unit), (value, _), (max, _)]) => {
+                        (min.max(value.min(*max)), unit)
+                    }
+                    _ => {
+                        return Calc::Function {
+                            function: *function,
+                            arguments,
+                        }
+                    }
+                };
+
+                leaf(result, unit.clone())
+            }
+        }
+    }
+
+    /// Flattens a chain of additions and subtractions into signed terms
+    fn collect_terms(&self, sign: f32, terms: &mut Vec<(f32, Calc)>) {
+        match self {
+            Calc::Operation {
+                left,
+                operator: operator @ (Operator::Add | Operator::Subtract),
+                right,
+            } => {
+                left.collect_terms(sign, terms);
+                match operator {
+                    Operator::Add => right.collect_terms(sign, terms),
+                    _ => right.collect_terms(-sign, terms),
+                }
+            }
+            Calc::Function {
+                function: MathFunction::Calc,
+                arguments,
+            } => match arguments.as_slice() {
+                [argument] => argument.collect_terms(sign, terms),
+                _ => terms.push((sign, self.simplify_node())),
+            },
+            _ => terms.push((sign, self.simplify_node())),
+        }
+    }
+
+    /// Computed value of the expression, with lengths in px, angles in deg,
+    /// times in s, frequencies in hz and resolutions in dppx.
+    ///
+    /// NaN results are 0 and infinite results are clamped to the largest
+    /// finite values, as CSS Values 4 requires, e.g. `calc(1px / 0)`.
+    ///
+    /// Returns `None` if a part of the expression can't be resolved in
+    /// `context`, e.g. a `var()` or a percentage without a basis.
+    pub fn evaluate(&self, context: &LengthContext) -> Option<Value> {
+        let (value, calc_type) = self.evaluate_node(context)?;
+        let value = match value.is_nan() {
+            true => 0.0,
+            false => value.clamp(f32::MIN, f32::MAX),
+        };
+
+        let value = match calc_type {
+            CalcType::Number => Value::Number(value.into()),
+            CalcType::Length => Value::Length(value.into(), Unit::Px),
+            CalcType::Angle => Value::Dimension(value.into(), Unit::Deg),
+            CalcType::Time => Value::Dimension(value.into(), Unit::S),
+            CalcType::Frequency => Value::Dimension(value.into(), Unit::Hz),
+            CalcType::Resolution => Value::Dimension(value.into(), Unit::Dppx),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    fn evaluate_node(&self, context: &LengthContext) -> Option<(f32, CalcType)> {
+        match self {
+            Calc::Value(value) => match **value {
+                Value::Percentage(ref number) => {
+                    Some((context.to_px(number.value, &Unit::Pct)?, CalcType::Length))
+                }
+                Value::Length(ref number, ref unit) => {
+                    Some((context.to_px(number.value, unit)?, CalcType::Length))
+                }
+                _ => {
+                    let (value, unit) = numeric(self)?;
+                    match unit {
+                        None => Some((value, CalcType::Number)),
+                        Some(unit) => {
+                            let (factor, _) = unit.canonical()?;
+                            Some((value * factor, unit_type(&unit)))
+                        }
+                    }
+                }
+            },
+            Calc::Operation {
+                left,
+                operator,
+                right,
+            } => {
+                let (a, left_type) = left.evaluate_node(context)?;
+                let (b, right_type) = right.evaluate_node(context)?;
+
+                match (operator, left_type, right_type) {
+                    (Operator::Add, _, _) if left_type == right_type => Some((a + b, left_type)),
+                    (Operator::Subtract, _, _) if left_type == right_type => {
+                        Some((a - b, left_type))
+                    }
+                    (Operator::Multiply, CalcType::Number, _) => Some((a * b, right_type)),
+                    (Operator::Multiply, _, CalcType::Number) => Some((a * b, left_type)),
+                    (Operator::Divide, _, CalcType::Number) => Some((a / b, left_type)),
+                    _ => None,
+                }
+            }
+            Calc::Function {
+                function,
+                arguments,
+            } => {
+                let values: Vec<(f32, CalcType)> = arguments
+                    .iter()
+                    .map(|argument| argument.evaluate_node(context))
+                    .collect::<Option<_>>()?;
+                let calc_type = values.first()?.1;
+
+                if values.iter().any(|(_, t)| *t != calc_type) {
+                    return None;
+                }
+                let numbers = values.iter().map(|(value, _)| *value);
+
+                let result = match (function, values.as_slice()) {
+                    (MathFunction::Calc, [(value, _)]) => *value,
+                    (MathFunction::Min, [_, ..]) => numbers.fold(f32::INFINITY, f32::min),
+                    (MathFunction::Max, [_, ..]) => numbers.fold(f32::NEG_INFINITY, f32::max),
+                    (MathFunction::Clamp, [(min, _), (value, _), (max, _)]) => {
+                        min.max(value.min(*max))
+                    }
+                    _ => return None,
+                };
+
+                Some((result, calc_type))
+            }
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Calc::Operation {
+                operator: Operator::Add | Operator::Subtract,
+                ..
+            } => 1,
+            Calc::Operation { .. } => 2,
+            _ => 3,
+        }
+    }
+}
+
+/// Sum of signed terms with numeric terms of compatible units added up
+fn simplify_sum(terms: Vec<(f32, Calc)>) -> Calc {
+    // Numeric terms grouped by canonical unit, with the first unit of each group
+    let mut groups: Vec<(Option<Unit>, f32, Option<Unit>, usize)> = Vec::new();
+    let mut others: Vec<(f32, Calc)> = Vec::new();
+
+    for (sign, term) in terms {
+        let (value, unit) = match numeric(&term) {
+            Some(numeric) => numeric,
+            None => {
+                others.push((sign, term));
+                continue;
+            }
+        };
+        let (factor, key) = match unit {
+            Some(ref unit) => match unit.canonical() {
+                Some((factor, canonical)) => (factor, Some(canonical)),
+                None => (1.0, Some(unit.clone())),
+            },
+            None => (1.0, None),
+        };
+
+        match groups.iter_mut().find(|group| group.0 == key) {
+            Some(group) => {
+                group.1 += sign * value * factor;
+                group.3 += 1;
+            }
+            None => groups.push((key, sign * value * factor, unit, 1)),
+        }
+    }
+
+    let mut result: Vec<(f32, Calc)> = groups
+        .into_iter()
+        .map(|(key, value, unit, count)| match (count, unit) {
+            // A single term keeps its unit
+            (1, Some(ref unit)) if Some(unit.clone()) != key => {
+                let factor = unit.canonical().map_or(1.0, |(factor, _)| factor);
+                (1.0, leaf(value / factor, Some(unit.clone())))
+            }
+            _ => (1.0, leaf(value, key)),
+        })
+        .collect();
+    result.extend(others);
+
+    let mut terms = result.into_iter();
+    let (sign, first) = terms.next().unwrap_or((1.0, leaf(0.0, None)));
+    let mut sum = match sign < 0.0 {
+        true => Calc::operation(leaf(-1.0, None), Operator::Multiply, first),
+        false => first,
+    };
+
+    for (sign, term) in terms {
+        let (operator, term) = match (sign < 0.0, numeric(&term)) {
+            (true, _) => (Operator::Subtract, term),
+            (false, Some((value, unit))) if value < 0.0 => (Operator::Subtract, leaf(-value, unit)),
+            (false, _) => (Operator::Add, term),
+        };
+        sum = Calc::operation(sum, operator, term);
+    }
+
+    sum
+}
+
+/// Number with its unit if `calc` is a numeric value, `None` unit for plain numbers
+fn numeric(calc: &Calc) -> Option<(f32, Option<Unit>)> {
+    match calc {
+        Calc::Value(value) => match **value {
+            Value::Integer(value) => Some((value as f32, None)),
+            Value::Number(ref number) => Some((number.value, None)),
+            Value::Percentage(ref number) => Some((number.value, Some(Unit::Pct))),
+            Value::Length(ref number, ref unit) | Value::Dimension(ref number, ref unit) => {
+                Some((number.value, Some(unit.clone())))
+            }
+            Value::Keyword(ref name) => match name.to_lowercase().as_str() {
+                "e" => Some((std::f32::consts::E, None)),
+                "pi" => Some((std::f32::consts::PI, None)),
+                "infinity" => Some((f32::INFINITY, None)),
+                "-infinity" => Some((f32::NEG_INFINITY, None)),
+                "nan" => Some((f32::NAN, None)),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Numeric value, infinite and NaN values are written as constants, e.g.
+/// `infinity * 1px`
+fn leaf(value: f32, unit: Option<Unit>) -> Calc {
+    if !value.is_finite() {
+        let constant = match value {
+            value if value.is_nan() => "NaN",
+            value if value > 0.0 => "infinity",
+            _ => "-infinity",
+        };
+        let constant = Calc::value(Value::Keyword(constant.to_string()));
+
+        return match unit {
+            None => constant,
+            Some(unit) => Calc::operation(constant, Operator::Multiply, leaf(1.0, Some(unit))),
+        };
+    }
+
+    Calc::value(match unit {
+        None => Value::Number(value.into()),
+        Some(Unit::Pct) => Value::Percentage(value.into()),
+        Some(unit) if unit.is_length() => Value::Length(value.into(), unit),
+        Some(unit) => Value::Dimension(value.into(), unit),
+    })
+}
+
+fn value_type(value: &Value) -> Result<CalcType, String> {
+    match value {
+        Value::Integer(_) | Value::Number(_) => Ok(CalcType::Number),
+        Value::Percentage(_) => Ok(CalcType::Percentage),
+        Value::Length(_, _) => Ok(CalcType::Length),
+        Value::Dimension(_, unit) if unit.kind() != UnitKind::Unknown => Ok(unit_type(unit)),
+        Value::Keyword(name)
+            if ["e", "pi", "infinity", "-infinity", "nan"]
+                .iter()
+                .any(|constant| name.eq_ignore_ascii_case(constant)) =>
+        {
+            Ok(CalcType::Number)
+        }
+        Value::Var { .. } => Ok(CalcType::Unknown),
+        _ => Err(format!("\"{}\" is not allowed in a math function", value)),
+    }
+}
+
+fn unit_type(unit: &Unit) -> CalcType {
+    match unit.kind() {
+        UnitKind::Length => CalcType::Length,
+        UnitKind::Angle => CalcType::Angle,
+        UnitKind::Time => CalcType::Time,
+        UnitKind::Frequency => CalcType::Frequency,
+        UnitKind::Resolution => CalcType::Resolution,
+        UnitKind::Flex => CalcType::Flex,
+        UnitKind::Percentage => CalcType::Percentage,
+        UnitKind::Unknown => CalcType::Unknown,
+    }
+}
+
+/// Type of a sum, percentages resolve to the type they are added to
+fn add_types(left: CalcType, right: CalcType) -> Result<CalcType, String> {
+    match (left, right) {
+        (CalcType::Unknown, other) | (other, CalcType::Unknown) => Ok(other),
+        (left, right) if left == right => Ok(left),
+        (CalcType::Percentage, other) | (other, CalcType::Percentage)
+            if other != CalcType::Number =>
+        {
+            Ok(other)
+        }
+        _ => Err(format!("cannot add {} and {}", left, right)),
+    }
+}
+
+impl fmt::Display for Calc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Calc::Value(value) => write!(f, "{}", value),
+            Calc::Operation {
+                left,
+                operator,
+                right,
+            } => {
+                let precedence = self.precedence();
+                let left = match left.precedence() < precedence {
+                    true => format!("({})", left),
+                    false => format!("{}", left),
+                };
+                let right = match right.precedence() < precedence
+                    || (right.precedence() == precedence
+                        && matches!(operator, Operator::Subtract | Operator::Divide))
+                {
+                    true => format!("({})", right),
+                    false => format!("{}", right),
+                };
+
+                write!(f, "{} {} {}", left, operator, right)
+            }
+            Calc::Function {
+                function,
+                arguments,
+            } => {
+                let arguments: Vec<String> = arguments.iter().map(|a| format!("{}", a)).collect();
+                write!(f, "{}({})", function, arguments.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operator::Add => write!(f, "+"),
+            Operator::Subtract => write!(f, "-"),
+            Operator::Multiply => write!(f, "*"),
+            Operator::Divide => write!(f, "/"),
+        }
+    }
+}
+
+impl fmt::Display for MathFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MathFunction::Calc => write!(f, "calc"),
+            MathFunction::Min => write!(f, "min"),
+            MathFunction::Max => write!(f, "max"),
+            MathFunction::Clamp => write!(f, "clamp"),
+        }
+    }
+}
+
+impl fmt::Display for CalcType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CalcType::Number => write!(f, "number"),
+            CalcType::Length => write!(f, "length"),
+            CalcType::Angle => write!(f, "angle"),
+            CalcType::Time => write!(f, "time"),
+            CalcType::Frequency => write!(f, "frequency"),
+            CalcType::Resolution => write!(f, "resolution"),
+            CalcType::Flex => write!(f, "flex"),
+            CalcType::Percentage => write!(f, "percentage"),
+            CalcType::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse_value;
+
+    fn calc(value: &str) -> Calc {
+        match parse_value("width", value) {
+            Value::Calc(calc) => calc,
+            value => panic!("\"{}\" is not a math function", value),
+        }
+    }
+
+    #[test]
+    fn calc_type() {
+        let tests = [
+            ("calc(1px + 2em)", Ok(CalcType::Length)),
+            ("calc(100% - 1rem)", Ok(CalcType::Length)),
+            ("calc(2 * 3)", Ok(CalcType::Number)),
+            ("calc(90deg / 2)", Ok(CalcType::Angle)),
+            ("calc(1px + var(--a))", Ok(CalcType::Length)),
+            ("clamp(1rem, 2.5vw, 2rem)", Ok(CalcType::Length)),
+            (
+                "calc(1 + 1%)",
+                Err("cannot add number and percentage".to_string()),
+            ),
+            (
+                "calc(1px / 1px)",
+                Err("cannot divide length by length".to_string()),
+            ),
+            (
+                "max(1s, 1ms, 1hz)",
+                Err("cannot add time and frequency".to_string()),
+            ),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(calc(value).calc_type(), expected, "calc_type {}", value);
+        }
+    }
+
+    #[test]
+    fn simplify() {
+        let tests = [
+            ("calc(10px + 1in)", "calc(106px)"),
+            ("calc(1px + 2em + 3px - 1em)", "calc(4px + 1em)"),
+            ("calc(100% - 2 * 8px)", "calc(100% - 16px)"),
+            ("calc(10px - 2 * var(--a))", "calc(10px - 2 * var(--a))"),
+            ("calc((1rem + 2rem) * 2)", "calc(6rem)"),
+            ("calc(1s + 500ms)", "calc(1.5s)"),
+            ("min(10px, 2px, 5px)", "calc(2px)"),
+            ("min(10px, 5vw)", "min(10px, 5vw)"),
+            ("clamp(1rem, 5rem, 2rem)", "calc(2rem)"),
+            ("calc(1px + calc(2px * 3))", "calc(7px)"),
+            ("calc(infinity * 1px)", "calc(infinity * 1px)"),
+            ("calc(1px - infinity * 1px)", "calc(1px - infinity * 1px)"),
+            ("calc(-infinity * 2)", "calc(-infinity)"),
+            ("calc(NaN + 1)", "calc(NaN)"),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(
+                format!("{}", calc(value).simplify()),
+                expected,
+                "simplify {}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn evaluate() {
+        let context = LengthContext {
+            percentage_basis: Some(200.0),
+            ..Default::default()
+        };
+        let tests = [
+            (
+                "calc(100% - 2rem)",
+                Some(Value::Length(168.0.into(), Unit::Px)),
+            ),
+            (
+                "clamp(1rem, 2.5vw, 2rem)",
+                Some(Value::Length(32.0.into(), Unit::Px)),
+            ),
+            ("min(1in, 50vh)", Some(Value::Length(96.0.into(), Unit::Px))),
+            ("calc(2 * 3 + 1)", Some(Value::Number(7.0.into()))),
+            (
+                "calc(1turn / 4)",
+                Some(Value::Dimension(90.0.into(), Unit::Deg)),
+            ),
+            ("calc(1px + var(--a))", None),
+            ("calc(1fr * 2)", None),
+            (
+                "calc(1px / 0)",
+                Some(Value::Length(f32::MAX.into(), Unit::Px)),
+            ),
+            (
+                "calc(-infinity * 1deg)",
+                Some(Value::Dimension(f32::MIN.into(), Unit::Deg)),
+            ),
+            ("calc(nan * 1px)", Some(Value::Length(0.0.into(), Unit::Px))),
+            ("calc(infinity - infinity)", Some(Value::Number(0.0.into()))),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(
+                calc(value).evaluate(&context),
+                expected,
+                "evaluate {}",
+                value
+            );
+        }
+        assert_eq!(
+            calc("calc(100% - 2rem)").evaluate(&LengthContext::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn wrong_arity() {
+        // The parser checks the number of arguments, nodes built in code may not
+        let px = |value: f32| Calc::Value(Box::new(Value::Length(value.into(), Unit::Px)));
+        let tests = [
+            Calc::Function {
+                function: MathFunction::Calc,
+                arguments: vec![],
+            },
+            Calc::Function {
+                function: MathFunction::Calc,
+                arguments: vec![px(1.), px(2.)],
+            },
+            Calc::Function {
+                function: MathFunction::Clamp,
+                arguments: vec![px(1.), px(2.)],
+            },
+            Calc::Function {
+                function: MathFunction::Min,
+                arguments: vec![],
+            },
+        ];
+
+        for calc in tests {
+            assert_eq!(calc.simplify(), calc, "simplify {}", calc);
+            assert_eq!(
+                calc.evaluate(&LengthContext::default()),
+                None,
+                "evaluate {}",
+                calc
+            );
+        }
+    }
+}
@@ -1,3 +1,10 @@
+pub mod contrast;
+pub mod conversion;
+pub mod difference;
+pub mod palette;
+pub mod serialization;
+pub mod vision;
+
 use std::default::Default;
 use std::fmt;
 
@@ -7,180 +14,288 @@ pub struct Color {
     pub pattern: String,
     pub data: ColorData,
 }
+/// Components of a color as written, `none` components are 0.
+///
+/// Hues are in degrees, percentages of hsl() and hwb() are kept as numbers
+/// from 0 to 100, and percentages of the other functions are resolved to
+/// numbers of the component's range, e.g. `lab(50% 0 0)` has `l: 50.`.
 #[derive(PartialEq, Clone, Debug)]
 pub enum ColorData {
-    RGBA { r: f32, g: f32, b: f32, a: f32 },
-    HSLA { h: f32, s: f32, l: f32, a: f32 },
+    /// Example: #ff0000, rgb(255 0 0), red
+    RGBA {
+        r: f32,
+        g: f32,
+        b: f32,
+        a: f32,
+    },
+    /// Example: hsl(0 100% 50%)
+    HSLA {
+        h: f32,
+        s: f32,
+        l: f32,
+        a: f32,
+    },
+    /// Example: hwb(0 0% 0%)
+    HWBA {
+        h: f32,
+        w: f32,
+        b: f32,
+        a: f32,
+    },
+    /// Example: lab(54.29 80.8 69.89)
+    LAB {
+        l: f32,
+        a: f32,
+        b: f32,
+        alpha: f32,
+    },
+    /// Example: lch(54.29 106.84 40.85)
+    LCH {
+        l: f32,
+        c: f32,
+        h: f32,
+        alpha: f32,
+    },
+    /// Example: oklab(0.628 0.225 0.126)
+    OKLAB {
+        l: f32,
+        a: f32,
+        b: f32,
+        alpha: f32,
+    },
+    /// Example: oklch(0.628 0.258 29.23)
+    OKLCH {
+        l: f32,
+        c: f32,
+        h: f32,
+        alpha: f32,
+    },
+    /// Example: color(display-p3 1 0 0)
+    COLOR {
+        space: ColorSpace,
+        components: [f32; 3],
+        alpha: f32,
+    },
+    /// currentColor, the value of the `color` property of the same element
+    CURRENTCOLOR,
+    /// Example: Canvas, ButtonText
+    SYSTEM(SystemColor),
+    /// Example: inherit, initial
+    KEYWORD(WideKeyword),
     NONE,
 }
+/// Predefined color space of color()
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ColorSpace {
+    Srgb,
+    SrgbLinear,
+    DisplayP3,
+    A98Rgb,
+    ProphotoRgb,
+    Rec2020,
+    XyzD50,
+    /// Also `xyz`, which is an alias of `xyz-d65`
+    XyzD65,
+}
 
-impl Color {
-    fn original_to_hex(value: &str) -> &str {
-        match value {
-            "black" => "#000000",
-            "silver" => "#c0c0c0",
-            "gray" => "#808080",
-            "white" => "#ffffff",
-            "maroon" => "#800000",
-            "red" => "#ff0000",
-            "purple" => "#800080",
-            "fuchsia" => "#ff00ff",
-            "green" => "#008000",
-            "lime" => "#00ff00",
-            "olive" => "#808000",
-            "yellow" => "#ffff00",
-            "navy" => "#000080",
-            "blue" => "#0000ff",
-            "teal" => "#008080",
-            "aqua" => "#00ffff",
-            "orange" => "#ffa500",
-            "aliceblue" => "#f0f8ff",
-            "antiquewhite" => "#faebd7",
-            "aquamarine" => "#7fffd4",
-            "azure" => "#f0ffff",
-            "beige" => "#f5f5dc",
-            "bisque" => "#ffe4c4",
-            "blanchedalmond" => "#ffebcd",
-            "blueviolet" => "#8a2be2",
-            "brown" => "#a52a2a",
-            "burlywood" => "#deb887",
-            "cadetblue" => "#5f9ea0",
-            "chartreuse" => "#7fff00",
-            "chocolate" => "#d2691e",
-            "coral" => "#ff7f50",
-            "cornflowerblue" => "#6495ed",
-            "cornsilk" => "#fff8dc",
-            "crimson" => "#dc143c",
-            "cyan" => "#00ffff",
-            "darkblue" => "#00008b",
-            "darkcyan" => "#008b8b",
-            "darkgoldenrod" => "#b8860b",
-            "darkgray" => "#a9a9a9",
-            "darkgreen" => "#006400",
-            "darkgrey" => "#a9a9a9",
-            "darkkhaki" => "#bdb76b",
-            "darkmagenta" => "#8b008b",
-            "darkolivegreen" => "#556b2f",
-            "darkorange" => "#ff8c00",
-            "darkorchid" => "#9932cc",
-            "darkred" => "#8b0000",
-            "darksalmon" => "#e9967a",
-            "darkseagreen" => "#8fbc8f",
-            "darkslateblue" => "#483d8b",
-            "darkslategray" => "#2f4f4f",
-            "darkslategrey" => "#2f4f4f",
-            "darkturquoise" => "#00ced1",
-            "darkviolet" => "#9400d3",
-            "deeppink" => "#ff1493",
-            "deepskyblue" => "#00bfff",
-            "dimgray" => "#696969",
-            "dimgrey" => "#696969",
-            "dodgerblue" => "#1e90ff",
-            "firebrick" => "#b22222",
-            "floralwhite" => "#fffaf0",
-            "forestgreen" => "#228b22",
-            "gainsboro" => "#dcdcdc",
-            "ghostwhite" => "#f8f8ff",
-            "gold" => "#ffd700",
-            "goldenrod" => "#daa520",
-            "greenyellow" => "#adff2f",
-            "grey" => "#808080",
-            "honeydew" => "#f0fff0",
-            "hotpink" => "#ff69b4",
-            "indianred" => "#cd5c5c",
-            "indigo" => "#4b0082",
-            "ivory" => "#fffff0",
-            "khaki" => "#f0e68c",
-            "lavender" => "#e6e6fa",
-            "lavenderblush" => "#fff0f5",
-            "lawngreen" => "#7cfc00",
-            "lemonchiffon" => "#fffacd",
-            "lightblue" => "#add8e6",
-            "lightcoral" => "#f08080",
-            "lightcyan" => "#e0ffff",
-            "lightgoldenrodyellow" => "#fafad2",
-            "lightgray" => "#d3d3d3",
-            "lightgreen" => "#90ee90",
-            "lightgrey" => "#d3d3d3",
-            "lightpink" => "#ffb6c1",
-            "lightsalmon" => "#ffa07a",
-            "lightseagreen" => "#20b2aa",
-            "lightskyblue" => "#87cefa",
-            "lightslategray" => "#778899",
-            "lightslategrey" => "#778899",
-            "lightsteelblue" => "#b0c4de",
-            "lightyellow" => "#ffffe0",
-            "limegreen" => "#32cd32",
-            "linen" => "#faf0e6",
-            "magenta" => "#ff00ff",
-            "mediumaquamarine" => "#66cdaa",
-            "mediumblue" => "#0000cd",
-            "mediumorchid" => "#ba55d3",
-            "mediumpurple" => "#9370db",
-            "mediumseagreen" => "#3cb371",
-            "mediumslateblue" => "#7b68ee",
-            "mediumspringgreen" => "#00fa9a",
-            "mediumturquoise" => "#48d1cc",
-            "mediumvioletred" => "#c71585",
-            "midnightblue" => "#191970",
-            "mintcream" => "#f5fffa",
-            "mistyrose" => "#ffe4e1",
-            "moccasin" => "#ffe4b5",
-            "navajowhite" => "#ffdead",
-            "oldlace" => "#fdf5e6",
-            "olivedrab" => "#6b8e23",
-            "orangered" => "#ff4500",
-            "orchid" => "#da70d6",
-            "palegoldenrod" => "#eee8aa",
-            "palegreen" => "#98fb98",
-            "paleturquoise" => "#afeeee",
-            "palevioletred" => "#db7093",
-            "papayawhip" => "#ffefd5",
-            "peachpuff" => "#ffdab9",
-            "peru" => "#cd853f",
-            "pink" => "#ffc0cb",
-            "plum" => "#dda0dd",
-            "powderblue" => "#b0e0e6",
-            "rosybrown" => "#bc8f8f",
-            "royalblue" => "#4169e1",
-            "saddlebrown" => "#8b4513",
-            "salmon" => "#fa8072",
-            "sandybrown" => "#f4a460",
-            "seagreen" => "#2e8b57",
-            "seashell" => "#fff5ee",
-            "sienna" => "#a0522d",
-            "skyblue" => "#87ceeb",
-            "slateblue" => "#6a5acd",
-            "slategray" => "#708090",
-            "slategrey" => "#708090",
-            "snow" => "#fffafa",
-            "springgreen" => "#00ff7f",
-            "steelblue" => "#4682b4",
-            "tan" => "#d2b48c",
-            "thistle" => "#d8bfd8",
-            "tomato" => "#ff6347",
-            "turquoise" => "#40e0d0",
-            "violet" => "#ee82ee",
-            "wheat" => "#f5deb3",
-            "whitesmoke" => "#f5f5f5",
-            "yellowgreen" => "#9acd32",
-            "rebeccapurple" => "#663399",
-            _ => "#000000",
-        }
-    }
+/// System color of CSS Color 4, its value is chosen by the user agent
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum SystemColor {
+    AccentColor,
+    AccentColorText,
+    ActiveText,
+    ButtonBorder,
+    ButtonFace,
+    ButtonText,
+    Canvas,
+    CanvasText,
+    Field,
+    FieldText,
+    GrayText,
+    Highlight,
+    HighlightText,
+    LinkText,
+    Mark,
+    MarkText,
+    SelectedItem,
+    SelectedItemText,
+    VisitedText,
+}
+
+/// CSS-wide keyword, valid for every property
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum WideKeyword {
+    Inherit,
+    Initial,
+    Unset,
+    Revert,
+    RevertLayer,
+}
 
-    fn original(original_color: &str) -> Self {
-        let hex_color = Color::original_to_hex(original_color);
+/// Named color keywords of CSS Color 4 and their sRGB channels
+pub const NAMED_COLORS: &[(&str, [u8; 3])] = &[
+    ("black", [0, 0, 0]),
+    ("silver", [192, 192, 192]),
+    ("gray", [128, 128, 128]),
+    ("white", [255, 255, 255]),
+    ("maroon", [128, 0, 0]),
+    ("red", [255, 0, 0]),
+    ("purple", [128, 0, 128]),
+    ("fuchsia", [255, 0, 255]),
+    ("green", [0, 128, 0]),
+    ("lime", [0, 255, 0]),
+    ("olive", [128, 128, 0]),
+    ("yellow", [255, 255, 0]),
+    ("navy", [0, 0, 128]),
+    ("blue", [0, 0, 255]),
+    ("teal", [0, 128, 128]),
+    ("aqua", [0, 255, 255]),
+    ("orange", [255, 165, 0]),
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkgrey", [169, 169, 169]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkslategrey", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("greenyellow", [173, 255, 47]),
+    ("grey", [128, 128, 128]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightgrey", [211, 211, 211]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightslategrey", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("oldlace", [253, 245, 230]),
+    ("olivedrab", [107, 142, 35]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("wheat", [245, 222, 179]),
+    ("whitesmoke", [245, 245, 245]),
+    ("yellowgreen", [154, 205, 50]),
+    ("rebeccapurple", [102, 51, 153]),
+];
 
+impl Color {
+    /// Color of a named color keyword
+    fn named(name: &str, [r, g, b]: [u8; 3]) -> Self {
         Color {
             pattern: "#XXXXXX".to_string(),
             data: ColorData::RGBA {
-                r: hex_to_digit(&hex_color[1..3]),
-                g: hex_to_digit(&hex_color[3..5]),
-                b: hex_to_digit(&hex_color[5..7]),
+                r: r as f32,
+                g: g as f32,
+                b: b as f32,
                 a: 1.,
             },
-            original: original_color.to_string(),
+            original: name.to_string(),
         }
     }
     pub fn none(color: &str) -> Self {
@@ -191,177 +306,50 @@ impl Color {
         }
     }
 
+    /// Color of a css color value. It is matched case-insensitively and
+    /// `original` keeps the text as written.
     pub fn new(color: &str) -> Self {
+        Color {
+            original: color.to_string(),
+            ..Color::parse(&color.to_lowercase())
+        }
+    }
+
+    /// Color of a lowercased css color value
+    fn parse(color: &str) -> Self {
+        if let Some(data) = Color::keyword_data(color) {
+            return Color::keyword(color, data);
+        }
+        if let Some((name, rgb)) = NAMED_COLORS.iter().find(|(name, _)| *name == color) {
+            return Color::named(name, *rgb);
+        }
         match color {
-            color if color == "black" => Color::original("black"),
-            color if color == "silver" => Color::original("silver"),
-            color if color == "gray" => Color::original("gray"),
-            color if color == "white" => Color::original("white"),
-            color if color == "maroon" => Color::original("maroon"),
-            color if color == "red" => Color::original("red"),
-            color if color == "purple" => Color::original("purple"),
-            color if color == "fuchsia" => Color::original("fuchsia"),
-            color if color == "green" => Color::original("green"),
-            color if color == "lime" => Color::original("lime"),
-            color if color == "olive" => Color::original("olive"),
-            color if color == "yellow" => Color::original("yellow"),
-            color if color == "navy" => Color::original("navy"),
-            color if color == "blue" => Color::original("blue"),
-            color if color == "teal" => Color::original("teal"),
-            color if color == "aqua" => Color::original("aqua"),
-            color if color == "orange" => Color::original("orange"),
-            color if color == "aliceblue" => Color::original("aliceblue"),
-
-            color if color == "antiquewhite" => Color::original("antiquewhite"),
-            color if color == "aquamarine" => Color::original("aquamarine"),
-            color if color == "azure" => Color::original("azure"),
-            color if color == "beige" => Color::original("beige"),
-            color if color == "bisque" => Color::original("bisque"),
-            color if color == "blanchedalmond" => Color::original("blanchedalmond"),
-            color if color == "blueviolet" => Color::original("blueviolet"),
-            color if color == "brown" => Color::original("brown"),
-            color if color == "burlywood" => Color::original("burlywood"),
-            color if color == "cadetblue" => Color::original("cadetblue"),
-            color if color == "chartreuse" => Color::original("chartreuse"),
-            color if color == "chocolate" => Color::original("chocolate"),
-            color if color == "coral" => Color::original("coral"),
-            color if color == "cornflowerblue" => Color::original("cornflowerblue"),
-            color if color == "cornsilk" => Color::original("cornsilk"),
-            color if color == "crimson" => Color::original("crimson"),
-            color if color == "cyan" => Color::original("cyan"),
-            color if color == "darkblue" => Color::original("darkblue"),
-            color if color == "darkcyan" => Color::original("darkcyan"),
-            color if color == "darkgoldenrod" => Color::original("darkgoldenrod"),
-            color if color == "darkgray" => Color::original("darkgray"),
-            color if color == "darkgreen" => Color::original("darkgreen"),
-            color if color == "darkgrey" => Color::original("darkgrey"),
-            color if color == "darkkhaki" => Color::original("darkkhaki"),
-            color if color == "darkmagenta" => Color::original("darkmagenta"),
-            color if color == "darkolivegreen" => Color::original("darkolivegreen"),
-            color if color == "darkorange" => Color::original("darkorange"),
-            color if color == "darkorchid" => Color::original("darkorchid"),
-            color if color == "darkred" => Color::original("darkred"),
-            color if color == "darksalmon" => Color::original("darksalmon"),
-            color if color == "darkseagreen" => Color::original("darkseagreen"),
-            color if color == "darkslateblue" => Color::original("darkslateblue"),
-            color if color == "darkslategray" => Color::original("darkslategray"),
-            color if color == "darkslategrey" => Color::original("darkslategrey"),
-            color if color == "darkturquoise" => Color::original("darkturquoise"),
-            color if color == "darkviolet" => Color::original("darkviolet"),
-            color if color == "deeppink" => Color::original("deeppink"),
-            color if color == "deepskyblue" => Color::original("deepskyblue"),
-            color if color == "dimgray" => Color::original("dimgray"),
-            color if color == "dimgrey" => Color::original("dimgrey"),
-            color if color == "dodgerblue" => Color::original("dodgerblue"),
-            color if color == "firebrick" => Color::original("firebrick"),
-            color if color == "floralwhite" => Color::original("floralwhite"),
-            color if color == "forestgreen" => Color::original("forestgreen"),
-            color if color == "gainsboro" => Color::original("gainsboro"),
-            color if color == "ghostwhite" => Color::original("ghostwhite"),
-            color if color == "gold" => Color::original("gold"),
-            color if color == "goldenrod" => Color::original("goldenrod"),
-            color if color == "greenyellow" => Color::original("greenyellow"),
-            color if color == "grey" => Color::original("grey"),
-            color if color == "honeydew" => Color::original("honeydew"),
-            color if color == "hotpink" => Color::original("hotpink"),
-            color if color == "indianred" => Color::original("indianred"),
-            color if color == "indigo" => Color::original("indigo"),
-            color if color == "ivory" => Color::original("ivory"),
-            color if color == "khaki" => Color::original("khaki"),
-            color if color == "lavender" => Color::original("lavender"),
-            color if color == "lavenderblush" => Color::original("lavenderblush"),
-            color if color == "lawngreen" => Color::original("lawngreen"),
-            color if color == "lemonchiffon" => Color::original("lemonchiffon"),
-            color if color == "lightblue" => Color::original("lightblue"),
-            color if color == "lightcoral" => Color::original("lightcoral"),
-            color if color == "lightcyan" => Color::original("lightcyan"),
-            color if color == "lightgoldenrodyellow" => Color::original("lightgoldenrodyellow"),
-            color if color == "lightgray" => Color::original("lightgray"),
-            color if color == "lightgreen" => Color::original("lightgreen"),
-            color if color == "lightgrey" => Color::original("lightgrey"),
-            color if color == "lightpink" => Color::original("lightpink"),
-            color if color == "lightsalmon" => Color::original("lightsalmon"),
-            color if color == "lightseagreen" => Color::original("lightseagreen"),
-            color if color == "lightskyblue" => Color::original("lightskyblue"),
-            color if color == "lightslategray" => Color::original("lightslategray"),
-            color if color == "lightslategrey" => Color::original("lightslategrey"),
-            color if color == "lightsteelblue" => Color::original("lightsteelblue"),
-            color if color == "lightyellow" => Color::original("lightyellow"),
-            color if color == "limegreen" => Color::original("limegreen"),
-            color if color == "linen" => Color::original("linen"),
-            color if color == "magenta" => Color::original("magenta"),
-            color if color == "mediumaquamarine" => Color::original("mediumaquamarine"),
-            color if color == "mediumblue" => Color::original("mediumblue"),
-            color if color == "mediumorchid" => Color::original("mediumorchid"),
-            color if color == "mediumpurple" => Color::original("mediumpurple"),
-            color if color == "mediumseagreen" => Color::original("mediumseagreen"),
-            color if color == "mediumslateblue" => Color::original("mediumslateblue"),
-            color if color == "mediumspringgreen" => Color::original("mediumspringgreen"),
-            color if color == "mediumturquoise" => Color::original("mediumturquoise"),
-            color if color == "mediumvioletred" => Color::original("mediumvioletred"),
-            color if color == "midnightblue" => Color::original("midnightblue"),
-            color if color == "mintcream" => Color::original("mintcream"),
-            color if color == "mistyrose" => Color::original("mistyrose"),
-            color if color == "moccasin" => Color::original("moccasin"),
-            color if color == "navajowhite" => Color::original("navajowhite"),
-            color if color == "oldlace" => Color::original("oldlace"),
-            color if color == "olivedrab" => Color::original("olivedrab"),
-            color if color == "orangered" => Color::original("orangered"),
-            color if color == "orchid" => Color::original("orchid"),
-            color if color == "palegoldenrod" => Color::original("palegoldenrod"),
-            color if color == "palegreen" => Color::original("palegreen"),
-            color if color == "paleturquoise" => Color::original("paleturquoise"),
-            color if color == "palevioletred" => Color::original("palevioletred"),
-            color if color == "papayawhip" => Color::original("papayawhip"),
-            color if color == "peachpuff" => Color::original("peachpuff"),
-            color if color == "peru" => Color::original("peru"),
-            color if color == "pink" => Color::original("pink"),
-            color if color == "plum" => Color::original("plum"),
-            color if color == "powderblue" => Color::original("powderblue"),
-            color if color == "rosybrown" => Color::original("rosybrown"),
-            color if color == "royalblue" => Color::original("royalblue"),
-            color if color == "saddlebrown" => Color::original("saddlebrown"),
-            color if color == "salmon" => Color::original("salmon"),
-            color if color == "sandybrown" => Color::original("sandybrown"),
-            color if color == "seagreen" => Color::original("seagreen"),
-            color if color == "seashell" => Color::original("seashell"),
-            color if color == "sienna" => Color::original("sienna"),
-            color if color == "skyblue" => Color::original("skyblue"),
-            color if color == "slateblue" => Color::original("slateblue"),
-            color if color == "slategray" => Color::original("slategray"),
-            color if color == "slategrey" => Color::original("slategrey"),
-            color if color == "snow" => Color::original("snow"),
-            color if color == "springgreen" => Color::original("springgreen"),
-            color if color == "steelblue" => Color::original("steelblue"),
-            color if color == "tan" => Color::original("tan"),
-            color if color == "thistle" => Color::original("thistle"),
-            color if color == "tomato" => Color::original("tomato"),
-            color if color == "turquoise" => Color::original("turquoise"),
-            color if color == "violet" => Color::original("violet"),
-            color if color == "wheat" => Color::original("wheat"),
-            color if color == "whitesmoke" => Color::original("whitesmoke"),
-            color if color == "yellowgreen" => Color::original("yellowgreen"),
-            color if color == "rebeccapurple" => Color::original("rebeccapurple"),
+            "transparent" => Color {
+                pattern: "#XXXXXXXX".to_string(),
+                data: ColorData::RGBA {
+                    r: 0.,
+                    g: 0.,
+                    b: 0.,
+                    a: 0.,
+                },
+                original: color.to_string(),
+            },
 
             color if color.len() < 4 => Color::none(color),
 
+            color
+                if color.starts_with("#") && !color[1..].chars().all(|c| c.is_ascii_hexdigit()) =>
+            {
+                Color::none(color)
+            }
             color if color.starts_with("#") => match color.len() {
                 9 => Color {
                     pattern: "#XXXXXXXX".to_string(),
                     data: ColorData::RGBA {
-                        r: hex_to_digit(
-                            &std::iter::repeat(&color[1..3]).take(2).collect::<String>()[..],
-                        ),
-                        g: hex_to_digit(
-                            &std::iter::repeat(&color[3..5]).take(2).collect::<String>()[..],
-                        ),
-                        b: hex_to_digit(
-                            &std::iter::repeat(&color[5..7]).take(2).collect::<String>()[..],
-                        ),
-                        a: hex_to_digit(
-                            &std::iter::repeat(&color[7..9]).take(2).collect::<String>()[..],
-                        ) as f32
-                            / 255.,
+                        r: hex_to_digit(&color[1..3]),
+                        g: hex_to_digit(&color[3..5]),
+                        b: hex_to_digit(&color[5..7]),
+                        a: hex_to_digit(&color[7..9]) / 255.,
                     },
                     original: color.to_string(),
                 },
@@ -378,190 +366,314 @@ impl Color {
                 5 => Color {
                     pattern: "#XXXX".to_string(),
                     data: ColorData::RGBA {
-                        r: hex_to_digit(
-                            &std::iter::repeat(&color[1..2]).take(2).collect::<String>()[..],
-                        ),
-                        g: hex_to_digit(
-                            &std::iter::repeat(&color[2..3]).take(2).collect::<String>()[..],
-                        ),
-                        b: hex_to_digit(
-                            &std::iter::repeat(&color[3..4]).take(2).collect::<String>()[..],
-                        ),
-                        a: hex_to_digit(
-                            &std::iter::repeat(&color[4..5]).take(2).collect::<String>()[..],
-                        ) as f32
-                            / 255.,
+                        r: hex_to_digit(&color[1..2].repeat(2)),
+                        g: hex_to_digit(&color[2..3].repeat(2)),
+                        b: hex_to_digit(&color[3..4].repeat(2)),
+                        a: hex_to_digit(&color[4..5].repeat(2)) / 255.,
                     },
                     original: color.to_string(),
                 },
                 4 => Color {
                     pattern: "#XXX".to_string(),
                     data: ColorData::RGBA {
-                        r: hex_to_digit(
-                            &std::iter::repeat(&color[1..2]).take(2).collect::<String>()[..],
-                        ),
-                        g: hex_to_digit(
-                            &std::iter::repeat(&color[2..3]).take(2).collect::<String>()[..],
-                        ),
-                        b: hex_to_digit(
-                            &std::iter::repeat(&color[3..4]).take(2).collect::<String>()[..],
-                        ),
+                        r: hex_to_digit(&color[1..2].repeat(2)),
+                        g: hex_to_digit(&color[2..3].repeat(2)),
+                        b: hex_to_digit(&color[3..4].repeat(2)),
                         a: 1.,
                     },
                     original: color.to_string(),
                 },
-                _ => Color::default(),
+                _ => Color::none(color),
             },
-            color if color.starts_with("rgba(") => {
-                let channels: Vec<&str> = color
-                    .trim_start_matches("rgba(")
-                    .trim_end_matches(")")
-                    .split(",")
-                    .collect();
-
-                match channels.len() {
-                    size if size < 3 => Color::none(color),
-                    _ => {
-                        let color_channels: Vec<f32> = channels
-                            .into_iter()
-                            .map(|s| {
-                                let s_trimmed = s.trim();
-                                let has_percentage = s_trimmed.ends_with("%");
-                                let num = match has_percentage {
-                                    true => s_trimmed.trim_end_matches('%'),
-                                    _ => s_trimmed,
-                                }
-                                .parse::<f32>()
-                                .expect("Parsing of color channel failed: rgba");
-
-                                match has_percentage {
-                                    true => num / 100. * 255.,
-                                    _ => num,
-                                }
-                            })
-                            .collect();
-
-                        Color {
-                            pattern: "rgba".to_string(),
-                            data: ColorData::RGBA {
-                                r: color_channels[0],
-                                g: color_channels[1],
-                                b: color_channels[2],
-                                a: color_channels[3] / 255.,
-                            },
-                            original: color.to_string(),
-                        }
-                    }
-                }
-            }
-            color if color.starts_with("rgb(") => {
-                let channels: Vec<&str> = color
-                    .trim_start_matches("rgb(")
-                    .trim_end_matches(")")
-                    .split(",")
-                    .collect();
-
-                match channels.len() {
-                    size if size < 3 => Color::none(color),
-                    _ => {
-                        let color_channels: Vec<f32> = channels
-                            .into_iter()
-                            .map(|s| {
-                                let s_trimmed = s.trim();
-                                let has_percentage = s_trimmed.ends_with("%");
-                                let num = match has_percentage {
-                                    true => s_trimmed.trim_end_matches('%'),
-                                    _ => s_trimmed,
-                                }
-                                .parse::<f32>()
-                                .unwrap();
-
-                                match has_percentage {
-                                    true => num / 100. * 255.,
-                                    _ => num,
-                                }
-                            })
-                            .collect();
-
-                        Color {
-                            pattern: "rgb".to_string(),
-                            data: ColorData::RGBA {
-                                r: color_channels[0],
-                                g: color_channels[1],
-                                b: color_channels[2],
-                                a: 1.,
-                            },
-                            original: color.to_string(),
-                        }
-                    }
-                }
+            color if color.starts_with("rgb(") || color.starts_with("rgba(") => Color::rgb(color),
+            color if color.starts_with("hsl(") || color.starts_with("hsla(") => Color::hsl(color),
+            color if color.starts_with("hwb(") => Color::hwb(color),
+            color if color.starts_with("lab(") => Color::lab(color, 100., 125.),
+            color if color.starts_with("lch(") => Color::lch(color, 100., 150.),
+            color if color.starts_with("oklab(") => Color::lab(color, 1., 0.4),
+            color if color.starts_with("oklch(") => Color::lch(color, 1., 0.4),
+            color if color.starts_with("color(") => Color::color_function(color),
+
+            _ => Color::none(color),
+        }
+    }
+}
+
+impl Color {
+    /// rgb() or rgba(), which is an alias of rgb()
+    fn rgb(color: &str) -> Self {
+        let data = function_arguments(color).and_then(|arguments| {
+            let components = components(arguments)?;
+            if mixed_types(arguments, &components[..3]) {
+                return None;
             }
-            color if color.starts_with("hsla(") => {
-                let channels: Vec<&str> = color
-                    .trim_start_matches("hsla(")
-                    .trim_end_matches(")")
-                    .split(",")
-                    .collect();
-
-                match channels.len() {
-                    size if size < 3 => Color::none(color),
-                    _ => {
-                        let color_channels: Vec<&str> =
-                            channels.into_iter().map(|s| s.trim()).collect();
-
-                        Color {
-                            pattern: "hsla".to_string(),
-                            data: ColorData::HSLA {
-                                h: color_channels[0].parse::<f32>().unwrap(),
-                                s: color_channels[1]
-                                    .trim_end_matches("%")
-                                    .parse::<f32>()
-                                    .unwrap(),
-                                l: color_channels[2]
-                                    .trim_end_matches("%")
-                                    .parse::<f32>()
-                                    .unwrap(),
-                                a: color_channels[3].trim().parse::<f32>().unwrap(),
-                            },
-                            original: color.to_string(),
-                        }
-                    }
-                }
+
+            Some(ColorData::RGBA {
+                r: clamped(components[0], 255.)?,
+                g: clamped(components[1], 255.)?,
+                b: clamped(components[2], 255.)?,
+                a: alpha(components.get(3))?,
+            })
+        });
+
+        Color::function(color, data)
+    }
+
+    /// hsl() or hsla(), which is an alias of hsl()
+    fn hsl(color: &str) -> Self {
+        let data = function_arguments(color).and_then(|arguments| {
+            let components = components(arguments)?;
+            if mixed_types(arguments, &components[1..3]) {
+                return None;
             }
-            color if color.starts_with("hsl(") => {
-                let channels: Vec<&str> = color
-                    .trim_start_matches("hsl(")
-                    .trim_end_matches(")")
-                    .split(",")
-                    .collect();
-
-                match channels.len() {
-                    size if size < 3 => Color::none(color),
-                    _ => {
-                        let color_channels: Vec<&str> =
-                            channels.into_iter().map(|s| s.trim()).collect();
-
-                        Color {
-                            pattern: "hsl".to_string(),
-                            data: ColorData::HSLA {
-                                h: color_channels[0].parse::<f32>().unwrap(),
-                                s: color_channels[1]
-                                    .trim_end_matches("%")
-                                    .parse::<f32>()
-                                    .unwrap(),
-                                l: color_channels[2]
-                                    .trim_end_matches("%")
-                                    .parse::<f32>()
-                                    .unwrap(),
-                                a: 1.,
-                            },
-                            original: color.to_string(),
-                        }
-                    }
-                }
+
+            Some(ColorData::HSLA {
+                h: hue(components[0])?,
+                s: clamped(components[1], 100.)?,
+                l: clamped(components[2], 100.)?,
+                a: alpha(components.get(3))?,
+            })
+        });
+
+        Color::function(color, data)
+    }
+
+    fn hwb(color: &str) -> Self {
+        let data = modern_arguments(color)
+            .and_then(components)
+            .and_then(|components| {
+                Some(ColorData::HWBA {
+                    h: hue(components[0])?,
+                    w: clamped(components[1], 100.)?,
+                    b: clamped(components[2], 100.)?,
+                    a: alpha(components.get(3))?,
+                })
+            });
+
+        Color::function(color, data)
+    }
+
+    /// lab() or oklab(), percentages are relative to the lightness range
+    /// and to the range of a and b
+    fn lab(color: &str, lightness: f32, range: f32) -> Self {
+        let data = modern_arguments(color)
+            .and_then(components)
+            .and_then(|components| {
+                let (l, a, b, alpha) = (
+                    number_or_percentage(components[0], lightness)?,
+                    number_or_percentage(components[1], range)?,
+                    number_or_percentage(components[2], range)?,
+                    alpha(components.get(3))?,
+                );
+
+                Some(match color.starts_with("ok") {
+                    true => ColorData::OKLAB { l, a, b, alpha },
+                    false => ColorData::LAB { l, a, b, alpha },
+                })
+            });
+
+        Color::function(color, data)
+    }
+
+    /// lch() or oklch(), percentages are relative to the lightness range
+    /// and to the chroma range
+    fn lch(color: &str, lightness: f32, chroma: f32) -> Self {
+        let data = modern_arguments(color)
+            .and_then(components)
+            .and_then(|components| {
+                let (l, c, h, alpha) = (
+                    number_or_percentage(components[0], lightness)?,
+                    number_or_percentage(components[1], chroma)?,
+                    hue(components[2])?,
+                    alpha(components.get(3))?,
+                );
+
+                Some(match color.starts_with("ok") {
+                    true => ColorData::OKLCH { l, c, h, alpha },
+                    false => ColorData::LCH { l, c, h, alpha },
+                })
+            });
+
+        Color::function(color, data)
+    }
+
+    /// color() with a predefined color space, e.g. `color(display-p3 1 0 0 / 0.5)`
+    fn color_function(color: &str) -> Self {
+        let data = modern_arguments(color).and_then(|arguments| {
+            let (space, arguments) = arguments.split_once(char::is_whitespace)?;
+            let space = ColorSpace::new(space)?;
+            let components = components(arguments.trim())?;
+
+            Some(ColorData::COLOR {
+                space,
+                components: [
+                    number_or_percentage(components[0], 1.)?,
+                    number_or_percentage(components[1], 1.)?,
+                    number_or_percentage(components[2], 1.)?,
+                ],
+                alpha: alpha(components.get(3))?,
+            })
+        });
+
+        Color::function(color, data)
+    }
+
+    /// Color with `data` written as a color function, e.g. the result of a conversion
+    pub fn from_data(data: ColorData) -> Self {
+        let original = format!("{}", data);
+
+        match data {
+            ColorData::NONE => Color::none(&original),
+            ColorData::CURRENTCOLOR | ColorData::SYSTEM(_) | ColorData::KEYWORD(_) => {
+                Color::keyword(&original, data)
             }
+            data => Color::function(&original, Some(data)),
+        }
+    }
 
-            _ => Color::none(color),
+    /// Data of `currentcolor`, a system color or a css-wide keyword
+    fn keyword_data(color: &str) -> Option<ColorData> {
+        match color {
+            "currentcolor" => Some(ColorData::CURRENTCOLOR),
+            _ => SystemColor::new(color)
+                .map(ColorData::SYSTEM)
+                .or_else(|| WideKeyword::new(color).map(ColorData::KEYWORD)),
+        }
+    }
+
+    /// Color of a keyword which is not a named color, the pattern is the keyword
+    fn keyword(color: &str, data: ColorData) -> Self {
+        Color {
+            pattern: color.to_string(),
+            data,
+            original: color.to_string(),
+        }
+    }
+
+    /// Color with `currentColor` replaced by `current`, the value of the `color`
+    /// property, e.g. for `border-color: currentColor`. Other colors are kept.
+    pub fn resolve_current_color(&self, current: &Color) -> Color {
+        match self.data {
+            ColorData::CURRENTCOLOR => current.clone(),
+            _ => self.clone(),
+        }
+    }
+
+    /// Color of a color function, the pattern is the function name
+    fn function(color: &str, data: Option<ColorData>) -> Self {
+        match data {
+            Some(data) => Color {
+                pattern: color[..color.find('(').unwrap_or(0)].to_string(),
+                data,
+                original: color.to_string(),
+            },
+            None => Color::none(color),
+        }
+    }
+}
+
+impl ColorSpace {
+    /// Color space of a lowercased color() space name
+    pub fn new(name: &str) -> Option<ColorSpace> {
+        match name {
+            "srgb" => Some(ColorSpace::Srgb),
+            "srgb-linear" => Some(ColorSpace::SrgbLinear),
+            "display-p3" => Some(ColorSpace::DisplayP3),
+            "a98-rgb" => Some(ColorSpace::A98Rgb),
+            "prophoto-rgb" => Some(ColorSpace::ProphotoRgb),
+            "rec2020" => Some(ColorSpace::Rec2020),
+            "xyz-d50" => Some(ColorSpace::XyzD50),
+            "xyz" | "xyz-d65" => Some(ColorSpace::XyzD65),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ColorSpace::Srgb => "srgb",
+            ColorSpace::SrgbLinear => "srgb-linear",
+            ColorSpace::DisplayP3 => "display-p3",
+            ColorSpace::A98Rgb => "a98-rgb",
+            ColorSpace::ProphotoRgb => "prophoto-rgb",
+            ColorSpace::Rec2020 => "rec2020",
+            ColorSpace::XyzD50 => "xyz-d50",
+            ColorSpace::XyzD65 => "xyz-d65",
+        }
+    }
+}
+
+impl SystemColor {
+    /// System color of a lowercased keyword
+    pub fn new(name: &str) -> Option<SystemColor> {
+        match name {
+            "accentcolor" => Some(SystemColor::AccentColor),
+            "accentcolortext" => Some(SystemColor::AccentColorText),
+            "activetext" => Some(SystemColor::ActiveText),
+            "buttonborder" => Some(SystemColor::ButtonBorder),
+            "buttonface" => Some(SystemColor::ButtonFace),
+            "buttontext" => Some(SystemColor::ButtonText),
+            "canvas" => Some(SystemColor::Canvas),
+            "canvastext" => Some(SystemColor::CanvasText),
+            "field" => Some(SystemColor::Field),
+            "fieldtext" => Some(SystemColor::FieldText),
+            "graytext" => Some(SystemColor::GrayText),
+            "highlight" => Some(SystemColor::Highlight),
+            "highlighttext" => Some(SystemColor::HighlightText),
+            "linktext" => Some(SystemColor::LinkText),
+            "mark" => Some(SystemColor::Mark),
+            "marktext" => Some(SystemColor::MarkText),
+            "selecteditem" => Some(SystemColor::SelectedItem),
+            "selecteditemtext" => Some(SystemColor::SelectedItemText),
+            "visitedtext" => Some(SystemColor::VisitedText),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            SystemColor::AccentColor => "AccentColor",
+            SystemColor::AccentColorText => "AccentColorText",
+            SystemColor::ActiveText => "ActiveText",
+            SystemColor::ButtonBorder => "ButtonBorder",
+            SystemColor::ButtonFace => "ButtonFace",
+            SystemColor::ButtonText => "ButtonText",
+            SystemColor::Canvas => "Canvas",
+            SystemColor::CanvasText => "CanvasText",
+            SystemColor::Field => "Field",
+            SystemColor::FieldText => "FieldText",
+            SystemColor::GrayText => "GrayText",
+            SystemColor::Highlight => "Highlight",
+            SystemColor::HighlightText => "HighlightText",
+            SystemColor::LinkText => "LinkText",
+            SystemColor::Mark => "Mark",
+            SystemColor::MarkText => "MarkText",
+            SystemColor::SelectedItem => "SelectedItem",
+            SystemColor::SelectedItemText => "SelectedItemText",
+            SystemColor::VisitedText => "VisitedText",
+        }
+    }
+}
+
+impl WideKeyword {
+    /// CSS-wide keyword of a lowercased keyword
+    pub fn new(name: &str) -> Option<WideKeyword> {
+        match name {
+            "inherit" => Some(WideKeyword::Inherit),
+            "initial" => Some(WideKeyword::Initial),
+            "unset" => Some(WideKeyword::Unset),
+            "revert" => Some(WideKeyword::Revert),
+            "revert-layer" => Some(WideKeyword::RevertLayer),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            WideKeyword::Inherit => "inherit",
+            WideKeyword::Initial => "initial",
+            WideKeyword::Unset => "unset",
+            WideKeyword::Revert => "revert",
+            WideKeyword::RevertLayer => "revert-layer",
         }
     }
 }
@@ -588,11 +700,158 @@ impl fmt::Display for Color {
     }
 }
 
+impl fmt::Display for ColorSpace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for SystemColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for WideKeyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Color function with the components of the data, or the keyword, which
+/// parses back to the same data, e.g. `oklch(0.7 0.1 200 / 0.5)`
+impl fmt::Display for ColorData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (function, components, alpha) = match *self {
+            ColorData::RGBA { r, g, b, a } => ("rgb", format!("{} {} {}", r, g, b), a),
+            ColorData::HSLA { h, s, l, a } => ("hsl", format!("{} {}% {}%", h, s, l), a),
+            ColorData::HWBA { h, w, b, a } => ("hwb", format!("{} {}% {}%", h, w, b), a),
+            ColorData::LAB { l, a, b, alpha } => ("lab", format!("{} {} {}", l, a, b), alpha),
+            ColorData::LCH { l, c, h, alpha } => ("lch", format!("{} {} {}", l, c, h), alpha),
+            ColorData::OKLAB { l, a, b, alpha } => ("oklab", format!("{} {} {}", l, a, b), alpha),
+            ColorData::OKLCH { l, c, h, alpha } => ("oklch", format!("{} {} {}", l, c, h), alpha),
+            ColorData::COLOR {
+                space,
+                components: [c1, c2, c3],
+                alpha,
+            } => ("color", format!("{} {} {} {}", space, c1, c2, c3), alpha),
+            ColorData::CURRENTCOLOR => return write!(f, "currentcolor"),
+            ColorData::SYSTEM(system) => return write!(f, "{}", system),
+            ColorData::KEYWORD(keyword) => return write!(f, "{}", keyword),
+            ColorData::NONE => return write!(f, "none"),
+        };
+
+        match alpha == 1. {
+            true => write!(f, "{}({})", function, components),
+            false => write!(f, "{}({} / {})", function, components, alpha),
+        }
+    }
+}
+
 fn hex_to_digit(num: &str) -> f32 {
-    (match u8::from_str_radix(num, 16) {
-        Ok(n) => n,
-        Err(_) => 0,
-    }) as f32
+    u8::from_str_radix(num, 16).unwrap_or_default() as f32
+}
+
+/// Arguments of a color function, e.g. `255 0 0 / 50%` in `rgb(255 0 0 / 50%)`
+fn function_arguments(color: &str) -> Option<&str> {
+    let start = color.find('(')?;
+
+    Some(color[start + 1..].strip_suffix(')')?.trim())
+}
+
+/// Arguments of a color function which only has the space-separated syntax
+fn modern_arguments(color: &str) -> Option<&str> {
+    function_arguments(color).filter(|arguments| !arguments.contains(','))
+}
+
+/// Components of color function arguments with alpha last, e.g. `255 0 0 / 50%`
+/// gives `["255", "0", "0", "50%"]`.
+///
+/// Both the legacy comma-separated syntax and the space-separated syntax with
+/// a slash before alpha are accepted, `none` is only allowed in the latter.
+fn components(arguments: &str) -> Option<Vec<&str>> {
+    let components: Vec<&str> = match arguments.contains(',') {
+        true => arguments.split(',').map(str::trim).collect(),
+        false => {
+            let mut parts = arguments.split('/');
+            let mut components: Vec<&str> = parts.next()?.split_whitespace().collect();
+
+            match (parts.next().map(str::trim), parts.next()) {
+                (None, _) => {}
+                (Some(alpha), None) if components.len() == 3 && !alpha.is_empty() => {
+                    components.push(alpha)
+                }
+                _ => return None,
+            }
+            components
+        }
+    };
+
+    let legacy = arguments.contains(',');
+    if legacy && components.iter().any(|c| *c == "none" || c.contains('/')) {
+        return None;
+    }
+
+    match components.len() {
+        3 => Some(components),
+        4 if legacy || arguments.contains('/') => Some(components),
+        _ => None,
+    }
+}
+
+/// Number, or percentage of `hundred_percent`; `none` is 0
+fn number_or_percentage(component: &str, hundred_percent: f32) -> Option<f32> {
+    if component == "none" {
+        return Some(0.);
+    }
+    let number = match component.strip_suffix('%') {
+        Some(percentage) => percentage.parse::<f32>().ok()? * hundred_percent / 100.,
+        None => component.parse::<f32>().ok()?,
+    };
+
+    number.is_finite().then_some(number)
+}
+
+/// Number or percentage clamped to 0..=`hundred_percent`
+fn clamped(component: &str, hundred_percent: f32) -> Option<f32> {
+    number_or_percentage(component, hundred_percent).map(|number| number.clamp(0., hundred_percent))
+}
+
+/// Whether components of the legacy comma-separated syntax mix numbers and
+/// percentages, e.g. `rgb(255, 50%, 0)`
+fn mixed_types(arguments: &str, components: &[&str]) -> bool {
+    arguments.contains(',')
+        && components
+            .iter()
+            .any(|component| component.ends_with('%') != components[0].ends_with('%'))
+}
+
+/// Hue in degrees, a number or an angle; `none` is 0
+fn hue(component: &str) -> Option<f32> {
+    if component == "none" {
+        return Some(0.);
+    }
+    let units = [
+        ("deg", 1.),
+        ("grad", 0.9),
+        ("rad", 180. / std::f32::consts::PI),
+        ("turn", 360.),
+    ];
+    let (number, factor) = units
+        .iter()
+        .find_map(|(unit, factor)| Some((component.strip_suffix(unit)?, *factor)))
+        .unwrap_or((component, 1.));
+    let hue = number.parse::<f32>().ok()? * factor;
+
+    hue.is_finite().then_some(hue)
+}
+
+/// Alpha between 0 and 1, a number or a percentage; 1 when omitted
+fn alpha(component: Option<&&str>) -> Option<f32> {
+    match component {
+        Some(component) => number_or_percentage(component, 1.).map(|alpha| alpha.clamp(0., 1.)),
+        None => Some(1.),
+    }
 }
 
 #[cfg(test)]
@@ -654,6 +913,42 @@ mod tests {
             "Parse \"{}\"",
             value
         );
+
+        let value = "#ff000080";
+
+        assert_eq!(
+            Color::new(value),
+            Color {
+                pattern: "#XXXXXXXX".to_string(),
+                data: ColorData::RGBA {
+                    r: 255.,
+                    g: 0.,
+                    b: 0.,
+                    a: 128. / 255.,
+                },
+                original: value.to_string()
+            },
+            "Parse \"{}\"",
+            value
+        );
+
+        let value = "#FFF";
+
+        assert_eq!(
+            Color::new(value),
+            Color {
+                pattern: "#XXX".to_string(),
+                data: ColorData::RGBA {
+                    r: 255.,
+                    g: 255.,
+                    b: 255.,
+                    a: 1.,
+                },
+                original: value.to_string()
+            },
+            "Parse \"{}\"",
+            value
+        );
     }
     #[test]
     fn parse_rgba() {
@@ -667,7 +962,25 @@ mod tests {
                     r: 255.,
                     g: 255.,
                     b: 255.,
-                    a: 0.54901963,
+                    a: 1.,
+                },
+                original: value.to_string()
+            },
+            "Parse \"{}\"",
+            value
+        );
+
+        let value = "rgba(255, 255, 255, 0.5)";
+
+        assert_eq!(
+            Color::new(value),
+            Color {
+                pattern: "rgba".to_string(),
+                data: ColorData::RGBA {
+                    r: 255.,
+                    g: 255.,
+                    b: 255.,
+                    a: 0.5,
                 },
                 original: value.to_string()
             },
@@ -1014,4 +1327,316 @@ mod tests {
             value
         );
     }
+    #[test]
+    fn parse_malformed_without_panic() {
+        for value in [
+            "rgba(1, x, 3)",
+            "rgb(1, 2, three)",
+            "hsla(10foo, 20%, 30%, 0.5)",
+            "rgb(none, 0, 0)",
+            "rgb(1 2 3 4)",
+            "rgb(1 2 / 3)",
+            "rgb(1 2 3 / 4 / 5)",
+            "rgba(1, 2, 3, 4, 5)",
+            "hsl(10 20% 30%%)",
+            "rgb(1 2 3",
+            "hsl(a, b, c)",
+            "rgb(255, 50%, 0)",
+            "rgba(100%, 0, 0, 0.5)",
+            "hsl(120, 100%, 50)",
+            "#12345",
+            "#ggg",
+            "#ééé",
+        ] {
+            assert_eq!(
+                Color::new(value),
+                Color::none(value),
+                "Parse \"{}\"",
+                value
+            );
+        }
+    }
+    #[test]
+    fn parse_rgba_without_alpha() {
+        let value = "rgba(255, 0, 0)";
+
+        assert_eq!(
+            Color::new(value).data,
+            ColorData::RGBA {
+                r: 255.,
+                g: 0.,
+                b: 0.,
+                a: 1.,
+            },
+            "Parse \"{}\"",
+            value
+        );
+    }
+    #[test]
+    fn parse_space_separated() {
+        let tests = [
+            (
+                "rgb(255 0 0 / 50%)",
+                ColorData::RGBA {
+                    r: 255.,
+                    g: 0.,
+                    b: 0.,
+                    a: 0.5,
+                },
+            ),
+            (
+                "rgba(100% none 0)",
+                ColorData::RGBA {
+                    r: 255.,
+                    g: 0.,
+                    b: 0.,
+                    a: 1.,
+                },
+            ),
+            (
+                "rgb(0 0 0 / .25)",
+                ColorData::RGBA {
+                    r: 0.,
+                    g: 0.,
+                    b: 0.,
+                    a: 0.25,
+                },
+            ),
+            (
+                "hsl(210deg 40% 50% / .3)",
+                ColorData::HSLA {
+                    h: 210.,
+                    s: 40.,
+                    l: 50.,
+                    a: 0.3,
+                },
+            ),
+            (
+                "hsla(0.5turn 40 50)",
+                ColorData::HSLA {
+                    h: 180.,
+                    s: 40.,
+                    l: 50.,
+                    a: 1.,
+                },
+            ),
+            (
+                "hsl(200grad, 100%, 50%, 150%)",
+                ColorData::HSLA {
+                    h: 180.,
+                    s: 100.,
+                    l: 50.,
+                    a: 1.,
+                },
+            ),
+            (
+                "hsl(none 0% 100% / none)",
+                ColorData::HSLA {
+                    h: 0.,
+                    s: 0.,
+                    l: 100.,
+                    a: 0.,
+                },
+            ),
+            (
+                "rgb(300 0 0)",
+                ColorData::RGBA {
+                    r: 255.,
+                    g: 0.,
+                    b: 0.,
+                    a: 1.,
+                },
+            ),
+            (
+                "rgb(-10% 120% 0)",
+                ColorData::RGBA {
+                    r: 0.,
+                    g: 255.,
+                    b: 0.,
+                    a: 1.,
+                },
+            ),
+            (
+                "hsl(0 150% -5%)",
+                ColorData::HSLA {
+                    h: 0.,
+                    s: 100.,
+                    l: 0.,
+                    a: 1.,
+                },
+            ),
+            (
+                "hwb(0 -10 200%)",
+                ColorData::HWBA {
+                    h: 0.,
+                    w: 0.,
+                    b: 100.,
+                    a: 1.,
+                },
+            ),
+        ];
+
+        for (value, data) in tests {
+            assert_eq!(Color::new(value).data, data, "Parse \"{}\"", value);
+        }
+
+        let value = "hsl(3.14159265rad 100% 50%)";
+        match Color::new(value).data {
+            ColorData::HSLA { h, .. } => assert!((h - 180.).abs() < 0.001, "Parse \"{}\"", value),
+            data => panic!("Parse \"{}\": {:?}", value, data),
+        }
+    }
+
+    #[test]
+    fn parse_color_functions() {
+        let tests = [
+            (
+                "hwb(120deg 10% 20% / 0.5)",
+                ColorData::HWBA {
+                    h: 120.,
+                    w: 10.,
+                    b: 20.,
+                    a: 0.5,
+                },
+            ),
+            (
+                "lab(50% -20 62.5%)",
+                ColorData::LAB {
+                    l: 50.,
+                    a: -20.,
+                    b: 78.125,
+                    alpha: 1.,
+                },
+            ),
+            (
+                "lch(52.2 72.2 50 / 50%)",
+                ColorData::LCH {
+                    l: 52.2,
+                    c: 72.2,
+                    h: 50.,
+                    alpha: 0.5,
+                },
+            ),
+            (
+                "oklab(40% 0.1 -25%)",
+                ColorData::OKLAB {
+                    l: 0.4,
+                    a: 0.1,
+                    b: -0.1,
+                    alpha: 1.,
+                },
+            ),
+            (
+                "oklch(0.7 0.15 0.5turn)",
+                ColorData::OKLCH {
+                    l: 0.7,
+                    c: 0.15,
+                    h: 180.,
+                    alpha: 1.,
+                },
+            ),
+            (
+                "oklch(none 50% none)",
+                ColorData::OKLCH {
+                    l: 0.,
+                    c: 0.2,
+                    h: 0.,
+                    alpha: 1.,
+                },
+            ),
+            (
+                "color(display-p3 1 0.5 0 / 0.25)",
+                ColorData::COLOR {
+                    space: ColorSpace::DisplayP3,
+                    components: [1., 0.5, 0.],
+                    alpha: 0.25,
+                },
+            ),
+            (
+                "color(xyz 50% 0.2 none)",
+                ColorData::COLOR {
+                    space: ColorSpace::XyzD65,
+                    components: [0.5, 0.2, 0.],
+                    alpha: 1.,
+                },
+            ),
+        ];
+
+        for (value, data) in tests {
+            let color = Color::new(value);
+
+            assert_eq!(color.data, data, "Parse \"{}\"", value);
+            assert_eq!(format!("{}", color), value, "Parse \"{}\"", value);
+        }
+
+        for value in [
+            "hwb(120, 10%, 20%)",
+            "lab(50 20)",
+            "oklch(0.7 0.1 10% / 1)",
+            "color(rgb 1 0 0)",
+            "color(srgb 1 0)",
+            "color(srgb)",
+        ] {
+            assert_eq!(Color::new(value), Color::none(value), "Parse \"{}\"", value);
+        }
+    }
+
+    #[test]
+    fn stringify_color_data() {
+        for value in [
+            "rgb(255 0 0 / 0.5)",
+            "hsl(210 40% 50%)",
+            "hwb(120 10% 20% / 0.5)",
+            "lab(50 -20 30)",
+            "lch(52.2 72.2 50)",
+            "oklab(0.4 0.1 -0.1)",
+            "oklch(0.7 0.15 180 / 0.3)",
+            "color(srgb-linear 1 0.5 0)",
+            "color(xyz-d50 0.1 0.2 0.3)",
+            "currentcolor",
+            "revert-layer",
+        ] {
+            let data = Color::new(value).data;
+
+            assert_eq!(format!("{}", data), value, "Parse \"{}\"", value);
+            assert_eq!(Color::new(&format!("{}", data)).data, data);
+        }
+    }
+
+    #[test]
+    fn parse_color_keywords() {
+        let tests = [
+            (
+                "transparent",
+                ColorData::RGBA {
+                    r: 0.,
+                    g: 0.,
+                    b: 0.,
+                    a: 0.,
+                },
+            ),
+            ("currentcolor", ColorData::CURRENTCOLOR),
+            ("canvas", ColorData::SYSTEM(SystemColor::Canvas)),
+            ("buttontext", ColorData::SYSTEM(SystemColor::ButtonText)),
+            ("inherit", ColorData::KEYWORD(WideKeyword::Inherit)),
+            ("initial", ColorData::KEYWORD(WideKeyword::Initial)),
+            ("unset", ColorData::KEYWORD(WideKeyword::Unset)),
+            ("inherited", ColorData::NONE),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(Color::new(value).data, expected, "Parse \"{}\"", value);
+        }
+
+        assert_eq!(format!("{}", Color::new("canvastext").data), "CanvasText");
+        assert_eq!(Color::new("currentcolor").to_xyz(), None);
+        assert_eq!(Color::new("canvas").to_xyz(), None);
+
+        let red = Color::new("red");
+        assert_eq!(Color::new("currentcolor").resolve_current_color(&red), red);
+        assert_eq!(
+            Color::new("canvas").resolve_current_color(&red),
+            Color::new("canvas")
+        );
+    }
 }
@@ -0,0 +1,200 @@
+//! Contrast between text and background colors.
+
+use super::{Color, ColorData};
+
+/// WCAG 2.1 minimum contrast ratios of normal text
+pub const WCAG_AA: f32 = 4.5;
+pub const WCAG_AAA: f32 = 7.;
+
+impl Color {
+    /// `self` drawn over `base`, as an rgb() color. Opaque colors are returned as rgb()
+    /// unchanged, `None` if one of the colors is not a valid color.
+    pub fn composite_over(&self, base: &Color) -> Option<Color> {
+        let (r, g, b, a) = rgba(self)?;
+        let (base_r, base_g, base_b, base_a) = rgba(base)?;
+
+        let alpha = a + base_a * (1. - a);
+        let channel = |c: f32, base: f32| match alpha == 0. {
+            true => 0.,
+            false => (c * a + base * base_a * (1. - a)) / alpha,
+        };
+
+        Some(Color::from_data(ColorData::RGBA {
+            r: channel(r, base_r),
+            g: channel(g, base_g),
+            b: channel(b, base_b),
+            a: alpha,
+        }))
+    }
+
+    /// WCAG 2.1 relative luminance, from 0 for black to 1 for white. Alpha is ignored.
+    pub fn relative_luminance(&self) -> Option<f32> {
+        let (r, g, b, _) = rgba(self)?;
+        let linear = |c: f32| {
+            let c = c / 255.;
+            match c <= 0.04045 {
+                true => c / 12.92,
+                false => ((c + 0.055) / 1.055).powf(2.4),
+            }
+        };
+
+        Some(0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b))
+    }
+
+    /// WCAG 2.1 contrast ratio, from 1 to 21. It doesn't depend on the order of the colors.
+    pub fn contrast_ratio(&self, other: &Color) -> Option<f32> {
+        let (a, b) = (self.relative_luminance()?, other.relative_luminance()?);
+
+        Some((a.max(b) + 0.05) / (a.min(b) + 0.05))
+    }
+
+    /// APCA lightness contrast (Lc) of `self` as text on `background`, from about
+    /// 106 for black on white to about -108 for white on black. Alpha is ignored.
+    pub fn apca_contrast(&self, background: &Color) -> Option<f32> {
+        let text = apca_luminance(self)?;
+        let background = apca_luminance(background)?;
+
+        if (background - text).abs() < 0.0005 {
+            return Some(0.);
+        }
+
+        let lc = match background > text {
+            // Dark text on a light background
+            true => {
+                let contrast = (background.powf(0.56) - text.powf(0.57)) * 1.14;
+                match contrast < 0.1 {
+                    true => 0.,
+                    false => contrast - 0.027,
+                }
+            }
+            false => {
+                let contrast = (background.powf(0.65) - text.powf(0.62)) * 1.14;
+                match contrast > -0.1 {
+                    true => 0.,
+                    false => contrast + 0.027,
+                }
+            }
+        };
+
+        Some(lc * 100.)
+    }
+}
+
+/// Channels from 0 to 255 in the sRGB gamut and alpha
+fn rgba(color: &Color) -> Option<(f32, f32, f32, f32)> {
+    match color.to_srgb()?.data {
+        ColorData::RGBA { r, g, b, a } => Some((r, g, b, a)),
+        _ => None,
+    }
+}
+
+/// Screen luminance of APCA 0.0.98G with the soft clamp of near-black colors
+fn apca_luminance(color: &Color) -> Option<f32> {
+    let (r, g, b, _) = rgba(color)?;
+    let channel = |c: f32| (c / 255.).powf(2.4);
+    let y = 0.2126729 * channel(r) + 0.7151522 * channel(g) + 0.072175 * channel(b);
+
+    Some(match y < 0.022 {
+        true => y + (0.022 - y).powf(1.414),
+        false => y,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(value: Option<f32>, expected: f32, tolerance: f32) -> bool {
+        value.is_some_and(|value| (value - expected).abs() <= tolerance)
+    }
+
+    #[test]
+    fn contrast_ratio() {
+        let white = Color::new("#fff");
+        let tests = [
+            ("#000", 21.),
+            ("#fff", 1.),
+            ("#777", 4.48),
+            ("#767676", 4.54),
+            ("hsl(0 100% 50%)", 4.0),
+        ];
+
+        for (value, expected) in tests {
+            let color = Color::new(value);
+
+            assert!(
+                close(color.contrast_ratio(&white), expected, 0.01),
+                "contrast_ratio of {} on white",
+                value
+            );
+            assert!(
+                close(white.contrast_ratio(&color), expected, 0.01),
+                "contrast_ratio of white on {}",
+                value
+            );
+        }
+        assert_eq!(white.contrast_ratio(&Color::new("none")), None);
+    }
+
+    #[test]
+    fn apca_contrast() {
+        let tests = [
+            ("#888", "#fff", 63.06),
+            ("#fff", "#888", -68.54),
+            ("#000", "#aaa", 58.15),
+            ("#aaa", "#000", -56.24),
+            ("#123", "#123", 0.),
+        ];
+
+        for (text, background, expected) in tests {
+            let lc = Color::new(text).apca_contrast(&Color::new(background));
+
+            assert!(
+                close(lc, expected, 0.05),
+                "apca_contrast of {} on {}: {:?}",
+                text,
+                background,
+                lc
+            );
+        }
+    }
+
+    #[test]
+    fn composite_over() {
+        let white = Color::new("#fff");
+
+        assert_eq!(
+            Color::new("rgb(0 0 0 / 50%)")
+                .composite_over(&white)
+                .unwrap()
+                .data,
+            ColorData::RGBA {
+                r: 127.5,
+                g: 127.5,
+                b: 127.5,
+                a: 1.
+            }
+        );
+        assert_eq!(
+            Color::new("#f00").composite_over(&white).unwrap().data,
+            ColorData::RGBA {
+                r: 255.,
+                g: 0.,
+                b: 0.,
+                a: 1.
+            }
+        );
+        assert_eq!(
+            Color::new("rgb(0 0 255 / 0.5)")
+                .composite_over(&Color::new("rgb(255 0 0 / 0.5)"))
+                .unwrap()
+                .data,
+            ColorData::RGBA {
+                r: 85.,
+                g: 0.,
+                b: 170.,
+                a: 0.75
+            }
+        );
+    }
+}
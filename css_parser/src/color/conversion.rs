@@ -0,0 +1,773 @@
+//! Conversion between color spaces with the matrices of CSS Color 4.
+//!
+//! Colors are converted through XYZ with a D65 white point. Conversions to
+//! spaces with a limited gamut, like sRGB or display-p3, reduce the OKLCH
+//! chroma of out-of-gamut colors until they fit, as CSS Color 4 gamut mapping
+//! does.
+
+use super::{Color, ColorData, ColorSpace};
+
+pub(crate) type Coords = [f64; 3];
+pub(crate) type Matrix = [[f64; 3]; 3];
+
+const LINEAR_SRGB_TO_XYZ: Matrix = [
+    [0.41239079926595934, 0.357584339383878, 0.1804807884018343],
+    [0.21263900587151027, 0.715168678767756, 0.07219231536073371],
+    [0.01933081871559182, 0.11919477979462598, 0.9505321522496607],
+];
+const XYZ_TO_LINEAR_SRGB: Matrix = [
+    [3.2409699419045226, -1.537383177570094, -0.4986107602930034],
+    [-0.9692436362808796, 1.8759675015077202, 0.04155505740717559],
+    [
+        0.05563007969699366,
+        -0.20397695888897652,
+        1.0569715142428786,
+    ],
+];
+const LINEAR_P3_TO_XYZ: Matrix = [
+    [0.4865709486482162, 0.26566769316909306, 0.1982172852343625],
+    [0.2289745640697488, 0.6917385218365064, 0.079286914093745],
+    [0.0, 0.04511338185890264, 1.043944368900976],
+];
+const XYZ_TO_LINEAR_P3: Matrix = [
+    [2.493496911941425, -0.9313836179191239, -0.40271078445071684],
+    [
+        -0.8294889695615747,
+        1.7626640603183463,
+        0.023624685841943577,
+    ],
+    [
+        0.03584583024378447,
+        -0.07617238926804182,
+        0.9568845240076872,
+    ],
+];
+const LINEAR_A98_TO_XYZ: Matrix = [
+    [0.5766690429101305, 0.1855582379065463, 0.1882286462349947],
+    [0.29734497525053605, 0.6273635662554661, 0.07529145849399788],
+    [0.02703136138641234, 0.07068885253582723, 0.9913375368376388],
+];
+const XYZ_TO_LINEAR_A98: Matrix = [
+    [
+        2.0415879038107465,
+        -0.5650069742788596,
+        -0.34473135077832956,
+    ],
+    [-0.9692436362808795, 1.8759675015077202, 0.04155505740717557],
+    [
+        0.013444280632031142,
+        -0.11836239223101838,
+        1.0151749943912054,
+    ],
+];
+/// ProPhoto RGB has a D50 white point
+const LINEAR_PROPHOTO_TO_XYZ_D50: Matrix = [
+    [0.7977604896723027, 0.13518583717574031, 0.0313493495815248],
+    [
+        0.2880711282292934,
+        0.7118432178101014,
+        0.00008565396060525902,
+    ],
+    [0.0, 0.0, 0.8251046025104601],
+];
+const XYZ_D50_TO_LINEAR_PROPHOTO: Matrix = [
+    [
+        1.3457989731028281,
+        -0.25558010007997534,
+        -0.05110628506753401,
+    ],
+    [-0.5446224939028347, 1.5082327413132781, 0.02053603239147973],
+    [0.0, 0.0, 1.2119675456389454],
+];
+const LINEAR_REC2020_TO_XYZ: Matrix = [
+    [0.6369580483012914, 0.14461690358620832, 0.1688809751641721],
+    [0.2627002120112671, 0.6779980715188708, 0.05930171646986196],
+    [0.0, 0.028072693049087428, 1.060985057710791],
+];
+const XYZ_TO_LINEAR_REC2020: Matrix = [
+    [1.716651187971268, -0.355670783776392, -0.25336628137366],
+    [-0.666684351832489, 1.616481236634939, 0.0157685458139111],
+    [0.017639857445311, -0.042770613257809, 0.942103121235474],
+];
+/// Bradford chromatic adaptation
+const D65_TO_D50: Matrix = [
+    [
+        1.0479297925449969,
+        0.022946870601609652,
+        -0.05019226628920524,
+    ],
+    [
+        0.02962780877005599,
+        0.9904344267538799,
+        -0.017073799063418826,
+    ],
+    [
+        -0.009243040646204504,
+        0.015055191490298152,
+        0.7518742814281371,
+    ],
+];
+const D50_TO_D65: Matrix = [
+    [0.955473421488075, -0.02309845494876471, 0.06325924320057072],
+    [
+        -0.0283697093338637,
+        1.0099953980813041,
+        0.021041441191917323,
+    ],
+    [
+        0.012314014864481998,
+        -0.020507649298898964,
+        1.330365926242124,
+    ],
+];
+const XYZ_TO_LMS: Matrix = [
+    [0.819022437996703, 0.3619062600528904, -0.1288737815209879],
+    [0.0329836539323885, 0.9292868615863434, 0.0361446663506424],
+    [0.0481771893596242, 0.2642395317527308, 0.6335478284694309],
+];
+const LMS_TO_OKLAB: Matrix = [
+    [0.210454268309314, 0.7936177747023054, -0.0040720430116193],
+    [1.9779985324311684, -2.42859224204858, 0.450593709617411],
+    [0.0259040424655478, 0.7827717124575296, -0.8086757549230774],
+];
+const OKLAB_TO_LMS: Matrix = [
+    [1.0, 0.3963377773761749, 0.2158037573099136],
+    [1.0, -0.1055613458156586, -0.0638541728258133],
+    [1.0, -0.0894841775298119, -1.2914855480194092],
+];
+const LMS_TO_XYZ: Matrix = [
+    [1.2268798758459243, -0.5578149944602171, 0.2813910456659647],
+    [-0.0405757452148008, 1.112286803280317, -0.0717110580655164],
+    [-0.0763729366746601, -0.4214933324022432, 1.5869240198367816],
+];
+/// D50 white point of Lab and LCH
+const D50_WHITE: Coords = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+const LAB_KAPPA: f64 = 24389.0 / 27.0;
+const LAB_EPSILON: f64 = 216.0 / 24389.0;
+
+/// Just noticeable difference in OKLab, the tolerance of gamut mapping
+const GAMUT_JND: f64 = 0.02;
+/// Tolerance of in-gamut checks, to absorb rounding of f32 components
+const GAMUT_EPSILON: f64 = 0.0001;
+
+impl Color {
+    /// Color as rgb() with channels from 0 to 255, mapped into the sRGB gamut
+    pub fn to_srgb(&self) -> Option<Color> {
+        let (xyz, alpha) = self.data.to_xyz()?;
+        let [r, g, b] = self.data.to_srgb_gamut(xyz);
+
+        Some(Color::from_data(ColorData::RGBA {
+            r: (r * 255.0) as f32,
+            g: (g * 255.0) as f32,
+            b: (b * 255.0) as f32,
+            a: alpha as f32,
+        }))
+    }
+
+    /// Color as hsl(), mapped into the sRGB gamut
+    pub fn to_hsl(&self) -> Option<Color> {
+        let (xyz, alpha) = self.data.to_xyz()?;
+        let [h, s, l] = srgb_to_hsl(self.data.to_srgb_gamut(xyz));
+
+        Some(Color::from_data(ColorData::HSLA {
+            h: h as f32,
+            s: (s * 100.0) as f32,
+            l: (l * 100.0) as f32,
+            a: alpha as f32,
+        }))
+    }
+
+    /// Color as hwb(), mapped into the sRGB gamut
+    pub fn to_hwb(&self) -> Option<Color> {
+        let (xyz, alpha) = self.data.to_xyz()?;
+        let [h, w, b] = srgb_to_hwb(self.data.to_srgb_gamut(xyz));
+
+        Some(Color::from_data(ColorData::HWBA {
+            h: h as f32,
+            w: (w * 100.0) as f32,
+            b: (b * 100.0) as f32,
+            a: alpha as f32,
+        }))
+    }
+
+    pub fn to_lab(&self) -> Option<Color> {
+        let (xyz, alpha) = self.data.to_xyz()?;
+        let [l, a, b] = xyz_to_lab(xyz);
+
+        Some(Color::from_data(ColorData::LAB {
+            l: l as f32,
+            a: a as f32,
+            b: b as f32,
+            alpha: alpha as f32,
+        }))
+    }
+
+    pub fn to_lch(&self) -> Option<Color> {
+        let (xyz, alpha) = self.data.to_xyz()?;
+        let [l, c, h] = lab_to_lch(xyz_to_lab(xyz), 0.0015);
+
+        Some(Color::from_data(ColorData::LCH {
+            l: l as f32,
+            c: c as f32,
+            h: h as f32,
+            alpha: alpha as f32,
+        }))
+    }
+
+    pub fn to_oklab(&self) -> Option<Color> {
+        let (xyz, alpha) = self.data.to_xyz()?;
+        let [l, a, b] = xyz_to_oklab(xyz);
+
+        Some(Color::from_data(ColorData::OKLAB {
+            l: l as f32,
+            a: a as f32,
+            b: b as f32,
+            alpha: alpha as f32,
+        }))
+    }
+
+    pub fn to_oklch(&self) -> Option<Color> {
+        let (xyz, alpha) = self.data.to_xyz()?;
+        let [l, c, h] = lab_to_lch(xyz_to_oklab(xyz), 0.000004);
+
+        Some(Color::from_data(ColorData::OKLCH {
+            l: l as f32,
+            c: c as f32,
+            h: h as f32,
+            alpha: alpha as f32,
+        }))
+    }
+
+    /// Color as color(xyz-d65)
+    pub fn to_xyz(&self) -> Option<Color> {
+        self.to_color_space(ColorSpace::XyzD65)
+    }
+
+    /// Color as color() in `space`, mapped into the gamut of RGB spaces
+    pub fn to_color_space(&self, space: ColorSpace) -> Option<Color> {
+        let (xyz, alpha) = self.data.to_xyz()?;
+        let components = match space {
+            ColorSpace::XyzD50 | ColorSpace::XyzD65 => xyz_to_space(space, xyz),
+            _ => gamut_map(xyz, space),
+        };
+
+        Some(Color::from_data(ColorData::COLOR {
+            space,
+            components: components.map(|c| c as f32),
+            alpha: alpha as f32,
+        }))
+    }
+
+    /// Whether the color can be shown in `space` without gamut mapping
+    pub fn in_gamut(&self, space: ColorSpace) -> bool {
+        match self.data.to_xyz() {
+            Some(_) if matches!(space, ColorSpace::XyzD50 | ColorSpace::XyzD65) => true,
+            Some((xyz, _)) => in_gamut(xyz_to_space(space, xyz)),
+            None => false,
+        }
+    }
+
+    /// Whether both colors are the same color, whatever syntax they are written in,
+    /// e.g. `hsl(0 100% 50%)` and `#f00`
+    pub fn same_color(&self, other: &Color) -> bool {
+        match (self.data.to_xyz(), other.data.to_xyz()) {
+            (Some((a, alpha_a)), Some((b, alpha_b))) => {
+                (0..3).all(|i| (a[i] - b[i]).abs() < 0.0005) && (alpha_a - alpha_b).abs() < 0.001
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ColorData {
+    /// XYZ D65 coordinates and alpha, `None` for `ColorData::NONE` and keywords
+    /// whose color is only known when the page is rendered, e.g. `currentColor`
+    pub(crate) fn to_xyz(&self) -> Option<(Coords, f64)> {
+        let f = |c: f32| c as f64;
+
+        let xyz = match *self {
+            ColorData::RGBA { r, g, b, .. } => {
+                space_to_xyz(ColorSpace::Srgb, [f(r) / 255.0, f(g) / 255.0, f(b) / 255.0])
+            }
+            ColorData::HSLA { h, s, l, .. } => space_to_xyz(
+                ColorSpace::Srgb,
+                hsl_to_srgb([f(h), f(s) / 100.0, f(l) / 100.0]),
+            ),
+            ColorData::HWBA { h, w, b, .. } => space_to_xyz(
+                ColorSpace::Srgb,
+                hwb_to_srgb([f(h), f(w) / 100.0, f(b) / 100.0]),
+            ),
+            ColorData::LAB { l, a, b, .. } => lab_to_xyz([f(l), f(a), f(b)]),
+            ColorData::LCH { l, c, h, .. } => lab_to_xyz(lch_to_lab([f(l), f(c), f(h)])),
+            ColorData::OKLAB { l, a, b, .. } => oklab_to_xyz([f(l), f(a), f(b)]),
+            ColorData::OKLCH { l, c, h, .. } => oklab_to_xyz(lch_to_lab([f(l), f(c), f(h)])),
+            ColorData::COLOR {
+                space, components, ..
+            } => space_to_xyz(space, components.map(f)),
+            ColorData::CURRENTCOLOR
+            | ColorData::SYSTEM(_)
+            | ColorData::KEYWORD(_)
+            | ColorData::NONE => return None,
+        };
+
+        let alpha = match *self {
+            ColorData::RGBA { a, .. } | ColorData::HSLA { a, .. } | ColorData::HWBA { a, .. } => a,
+            ColorData::LAB { alpha, .. }
+            | ColorData::LCH { alpha, .. }
+            | ColorData::OKLAB { alpha, .. }
+            | ColorData::OKLCH { alpha, .. }
+            | ColorData::COLOR { alpha, .. } => alpha,
+            ColorData::CURRENTCOLOR
+            | ColorData::SYSTEM(_)
+            | ColorData::KEYWORD(_)
+            | ColorData::NONE => return None,
+        };
+
+        Some((xyz, f(alpha)))
+    }
+}
+
+impl ColorData {
+    /// Gamma-encoded sRGB coordinates mapped into the gamut, colors defined in
+    /// sRGB skip the round trip through `xyz` to keep their exact values
+    fn to_srgb_gamut(&self, xyz: Coords) -> Coords {
+        let f = |c: f32| c as f64;
+        let srgb = match *self {
+            ColorData::RGBA { r, g, b, .. } => [f(r) / 255.0, f(g) / 255.0, f(b) / 255.0],
+            ColorData::HSLA { h, s, l, .. } => hsl_to_srgb([f(h), f(s) / 100.0, f(l) / 100.0]),
+            ColorData::HWBA { h, w, b, .. } => hwb_to_srgb([f(h), f(w) / 100.0, f(b) / 100.0]),
+            ColorData::COLOR {
+                space: ColorSpace::Srgb,
+                components,
+                ..
+            } => components.map(f),
+            _ => return gamut_map(xyz, ColorSpace::Srgb),
+        };
+
+        match in_gamut(srgb) {
+            true => srgb.map(|c| c.clamp(0.0, 1.0)),
+            false => gamut_map(xyz, ColorSpace::Srgb),
+        }
+    }
+}
+
+/// Gamma-encoded coordinates of `xyz` in the RGB `space`, with the chroma reduced
+/// in OKLCH until the color fits in the gamut
+fn gamut_map(xyz: Coords, space: ColorSpace) -> Coords {
+    let origin = xyz_to_space(space, xyz);
+    if in_gamut(origin) {
+        return origin.map(|c| c.clamp(0.0, 1.0));
+    }
+
+    let [lightness, chroma, hue] = lab_to_lch(xyz_to_oklab(xyz), 0.0);
+    if lightness >= 1.0 {
+        return [1.0; 3];
+    }
+    if lightness <= 0.0 {
+        return [0.0; 3];
+    }
+
+    let to_space = |chroma: f64| {
+        let oklab = lch_to_lab([lightness, chroma, hue]);
+        (oklab, xyz_to_space(space, oklab_to_xyz(oklab)))
+    };
+    let clip = |coords: Coords| coords.map(|c| c.clamp(0.0, 1.0));
+    let delta_e =
+        |oklab: Coords, coords: Coords| distance(oklab, xyz_to_oklab(space_to_xyz(space, coords)));
+
+    let (oklab, current) = to_space(chroma);
+    let mut clipped = clip(current);
+    if delta_e(oklab, clipped) < GAMUT_JND {
+        return clipped;
+    }
+
+    let (mut min, mut max) = (0.0, chroma);
+    let mut min_in_gamut = true;
+
+    while max - min > GAMUT_EPSILON {
+        let chroma = (min + max) / 2.0;
+        let (oklab, current) = to_space(chroma);
+
+        if min_in_gamut && in_gamut(current) {
+            min = chroma;
+            continue;
+        }
+        clipped = clip(current);
+        let error = delta_e(oklab, clipped);
+
+        if error < GAMUT_JND {
+            if GAMUT_JND - error < GAMUT_EPSILON {
+                break;
+            }
+            min_in_gamut = false;
+            min = chroma;
+        } else {
+            max = chroma;
+        }
+    }
+
+    clipped
+}
+
+fn in_gamut(coords: Coords) -> bool {
+    coords
+        .iter()
+        .all(|c| (-GAMUT_EPSILON..=1.0 + GAMUT_EPSILON).contains(c))
+}
+
+/// XYZ D65 coordinates of gamma-encoded coordinates in `space`
+fn space_to_xyz(space: ColorSpace, coords: Coords) -> Coords {
+    match space {
+        ColorSpace::Srgb => multiply(LINEAR_SRGB_TO_XYZ, coords.map(srgb_to_linear)),
+        ColorSpace::SrgbLinear => multiply(LINEAR_SRGB_TO_XYZ, coords),
+        ColorSpace::DisplayP3 => multiply(LINEAR_P3_TO_XYZ, coords.map(srgb_to_linear)),
+        ColorSpace::A98Rgb => multiply(LINEAR_A98_TO_XYZ, coords.map(a98_to_linear)),
+        ColorSpace::ProphotoRgb => multiply(
+            D50_TO_D65,
+            multiply(LINEAR_PROPHOTO_TO_XYZ_D50, coords.map(prophoto_to_linear)),
+        ),
+        ColorSpace::Rec2020 => multiply(LINEAR_REC2020_TO_XYZ, coords.map(rec2020_to_linear)),
+        ColorSpace::XyzD50 => multiply(D50_TO_D65, coords),
+        ColorSpace::XyzD65 => coords,
+    }
+}
+
+/// Gamma-encoded coordinates in `space` of XYZ D65 coordinates, not gamut mapped
+fn xyz_to_space(space: ColorSpace, xyz: Coords) -> Coords {
+    match space {
+        ColorSpace::Srgb => multiply(XYZ_TO_LINEAR_SRGB, xyz).map(linear_to_srgb),
+        ColorSpace::SrgbLinear => multiply(XYZ_TO_LINEAR_SRGB, xyz),
+        ColorSpace::DisplayP3 => multiply(XYZ_TO_LINEAR_P3, xyz).map(linear_to_srgb),
+        ColorSpace::A98Rgb => multiply(XYZ_TO_LINEAR_A98, xyz).map(linear_to_a98),
+        ColorSpace::ProphotoRgb => {
+            multiply(XYZ_D50_TO_LINEAR_PROPHOTO, multiply(D65_TO_D50, xyz)).map(linear_to_prophoto)
+        }
+        ColorSpace::Rec2020 => multiply(XYZ_TO_LINEAR_REC2020, xyz).map(linear_to_rec2020),
+        ColorSpace::XyzD50 => multiply(D65_TO_D50, xyz),
+        ColorSpace::XyzD65 => xyz,
+    }
+}
+
+/// sRGB and display-p3 transfer function
+pub(crate) fn srgb_to_linear(c: f64) -> f64 {
+    match c.abs() <= 0.04045 {
+        true => c / 12.92,
+        false => c.signum() * ((c.abs() + 0.055) / 1.055).powf(2.4),
+    }
+}
+
+pub(crate) fn linear_to_srgb(c: f64) -> f64 {
+    match c.abs() <= 0.0031308 {
+        true => c * 12.92,
+        false => c.signum() * (1.055 * c.abs().powf(1.0 / 2.4) - 0.055),
+    }
+}
+
+fn a98_to_linear(c: f64) -> f64 {
+    c.signum() * c.abs().powf(563.0 / 256.0)
+}
+
+fn linear_to_a98(c: f64) -> f64 {
+    c.signum() * c.abs().powf(256.0 / 563.0)
+}
+
+fn prophoto_to_linear(c: f64) -> f64 {
+    match c.abs() <= 16.0 / 512.0 {
+        true => c / 16.0,
+        false => c.signum() * c.abs().powf(1.8),
+    }
+}
+
+fn linear_to_prophoto(c: f64) -> f64 {
+    match c.abs() >= 1.0 / 512.0 {
+        true => c.signum() * c.abs().powf(1.0 / 1.8),
+        false => c * 16.0,
+    }
+}
+
+const REC2020_ALPHA: f64 = 1.09929682680944;
+const REC2020_BETA: f64 = 0.018053968510807;
+
+fn rec2020_to_linear(c: f64) -> f64 {
+    match c.abs() < REC2020_BETA * 4.5 {
+        true => c / 4.5,
+        false => c.signum() * ((c.abs() + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45),
+    }
+}
+
+fn linear_to_rec2020(c: f64) -> f64 {
+    match c.abs() > REC2020_BETA {
+        true => c.signum() * (REC2020_ALPHA * c.abs().powf(0.45) - (REC2020_ALPHA - 1.0)),
+        false => c * 4.5,
+    }
+}
+
+/// CIE Lab with a D50 white point
+pub(crate) fn xyz_to_lab(xyz: Coords) -> Coords {
+    let xyz = multiply(D65_TO_D50, xyz);
+    let [x, y, z] = [0, 1, 2].map(|i| {
+        let value = xyz[i] / D50_WHITE[i];
+        match value > LAB_EPSILON {
+            true => value.cbrt(),
+            false => (LAB_KAPPA * value + 16.0) / 116.0,
+        }
+    });
+
+    [116.0 * y - 16.0, 500.0 * (x - y), 200.0 * (y - z)]
+}
+
+fn lab_to_xyz([l, a, b]: Coords) -> Coords {
+    let y = (l + 16.0) / 116.0;
+    let x = a / 500.0 + y;
+    let z = y - b / 200.0;
+    let inverse = |f: f64| match f.powi(3) > LAB_EPSILON {
+        true => f.powi(3),
+        false => (116.0 * f - 16.0) / LAB_KAPPA,
+    };
+    let y = match l > LAB_KAPPA * LAB_EPSILON {
+        true => y.powi(3),
+        false => l / LAB_KAPPA,
+    };
+    let xyz = [inverse(x), y, inverse(z)];
+
+    multiply(D50_TO_D65, [0, 1, 2].map(|i| xyz[i] * D50_WHITE[i]))
+}
+
+pub(crate) fn xyz_to_oklab(xyz: Coords) -> Coords {
+    multiply(LMS_TO_OKLAB, multiply(XYZ_TO_LMS, xyz).map(f64::cbrt))
+}
+
+fn oklab_to_xyz(oklab: Coords) -> Coords {
+    multiply(LMS_TO_XYZ, multiply(OKLAB_TO_LMS, oklab).map(|c| c.powi(3)))
+}
+
+/// Polar form of Lab or OKLab, the hue is 0 when the chroma is below
+/// `achromatic`, where it is powerless
+fn lab_to_lch([l, a, b]: Coords, achromatic: f64) -> Coords {
+    let chroma = a.hypot(b);
+    let hue = match chroma <= achromatic {
+        true => 0.0,
+        false => b.atan2(a).to_degrees().rem_euclid(360.0),
+    };
+
+    [l, chroma, hue]
+}
+
+fn lch_to_lab([l, c, h]: Coords) -> Coords {
+    let (sin, cos) = h.to_radians().sin_cos();
+
+    [l, c.max(0.0) * cos, c.max(0.0) * sin]
+}
+
+/// sRGB of hue in degrees and saturation and lightness from 0 to 1
+fn hsl_to_srgb([h, s, l]: Coords) -> Coords {
+    let h = h.rem_euclid(360.0);
+    let a = s * l.min(1.0 - l);
+    let channel = |n: f64| {
+        let k = (n + h / 30.0) % 12.0;
+        l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+
+    [channel(0.0), channel(8.0), channel(4.0)]
+}
+
+fn srgb_to_hsl([r, g, b]: Coords) -> Coords {
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta.abs() < 1e-9 {
+        return [0.0, 0.0, lightness];
+    }
+    let saturation = match lightness == 0.0 || lightness == 1.0 {
+        true => 0.0,
+        false => (max - lightness) / lightness.min(1.0 - lightness),
+    };
+
+    [hue(r, g, b, max, delta), saturation, lightness]
+}
+
+/// sRGB of hue in degrees and whiteness and blackness from 0 to 1
+fn hwb_to_srgb([h, w, b]: Coords) -> Coords {
+    if w + b >= 1.0 {
+        let gray = w / (w + b);
+        return [gray; 3];
+    }
+
+    hsl_to_srgb([h, 1.0, 0.5]).map(|c| c * (1.0 - w - b) + w)
+}
+
+fn srgb_to_hwb([r, g, b]: Coords) -> Coords {
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let hue = match max - min < 1e-9 {
+        true => 0.0,
+        false => hue(r, g, b, max, max - min),
+    };
+
+    [hue, min, 1.0 - max]
+}
+
+/// Hue in degrees of an sRGB color with chroma `delta`
+fn hue(r: f64, g: f64, b: f64, max: f64, delta: f64) -> f64 {
+    let hue = if max == r {
+        (g - b) / delta + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+
+    (hue * 60.0).rem_euclid(360.0)
+}
+
+/// Euclidean distance, the OKLab color difference for OKLab coordinates
+pub(crate) fn distance(a: Coords, b: Coords) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+pub(crate) fn multiply(matrix: Matrix, [x, y, z]: Coords) -> Coords {
+    matrix.map(|row| row[0] * x + row[1] * y + row[2] * z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that `color` has the components of `expected` within `tolerance`
+    fn assert_close(color: Option<Color>, expected: &str, tolerance: f32) {
+        let color = color.unwrap();
+        let components = |color: &str| -> Vec<f32> {
+            color
+                .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+                .filter_map(|c| c.parse().ok())
+                .collect()
+        };
+        let (actual, expected_components) = (components(&color.original), components(expected));
+
+        assert!(
+            actual.len() == expected_components.len()
+                && actual
+                    .iter()
+                    .zip(expected_components)
+                    .all(|(a, b)| (a - b).abs() <= tolerance),
+            "Convert to \"{}\": \"{}\"",
+            expected,
+            color
+        );
+    }
+
+    #[test]
+    fn convert() {
+        let red = Color::new("#ff0000");
+
+        assert_close(red.to_srgb(), "rgb(255 0 0)", 0.01);
+        assert_close(red.to_hsl(), "hsl(0 100% 50%)", 0.01);
+        assert_close(red.to_hwb(), "hwb(0 0% 0%)", 0.01);
+        assert_close(red.to_lab(), "lab(54.29 80.8 69.89)", 0.02);
+        assert_close(red.to_lch(), "lch(54.29 106.84 40.85)", 0.02);
+        assert_close(red.to_oklab(), "oklab(0.628 0.2249 0.1258)", 0.001);
+        assert_close(red.to_oklch(), "oklch(0.628 0.2577 29.23)", 0.01);
+        assert_close(red.to_xyz(), "color(xyz-d65 0.4124 0.2126 0.0193)", 0.0001);
+        assert_close(
+            red.to_color_space(ColorSpace::DisplayP3),
+            "color(display-p3 0.9175 0.2003 0.1386)",
+            0.001,
+        );
+
+        let gray = Color::new("hsl(120 0% 50% / 0.5)");
+        assert_close(gray.to_oklch(), "oklch(0.5987 0 0 / 0.5)", 0.001);
+        assert_close(gray.to_hwb(), "hwb(0 50% 50% / 0.5)", 0.01);
+
+        assert_eq!(Color::new("none").to_srgb(), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        for value in [
+            "rgb(12 34 56)",
+            "hsl(200 30% 40%)",
+            "hwb(300 20% 10%)",
+            "lab(40 30 -20)",
+            "lch(60 40 120)",
+            "oklab(0.5 -0.05 0.1)",
+            "oklch(0.7 0.1 250)",
+            "color(a98-rgb 0.2 0.4 0.6)",
+            "color(prophoto-rgb 0.3 0.5 0.2)",
+            "color(rec2020 0.6 0.3 0.4)",
+            "color(srgb-linear 0.1 0.2 0.3)",
+            "color(xyz-d50 0.2 0.3 0.1)",
+        ] {
+            let color = Color::new(value);
+            let conversions = [
+                color.to_oklch(),
+                color.to_lab(),
+                color.to_xyz(),
+                color.to_color_space(ColorSpace::XyzD50),
+            ];
+
+            for converted in conversions {
+                let converted = converted.unwrap();
+                assert!(
+                    color.same_color(&converted),
+                    "Convert \"{}\" to \"{}\"",
+                    value,
+                    converted
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn same_color() {
+        let red = Color::new("#f00");
+
+        for value in ["red", "rgb(255 0 0)", "hsl(0 100% 50%)", "hwb(0 0% 0%)"] {
+            assert!(red.same_color(&Color::new(value)), "same_color {}", value);
+        }
+        assert!(!red.same_color(&Color::new("#fe0000")));
+        assert!(!red.same_color(&Color::new("rgb(255 0 0 / 0.5)")));
+    }
+
+    #[test]
+    fn gamut_mapping() {
+        let p3 = Color::new("color(display-p3 1 0 0)");
+        assert!(!p3.in_gamut(ColorSpace::Srgb));
+        assert!(p3.in_gamut(ColorSpace::DisplayP3));
+        assert!(Color::new("color(display-p3 0.9 0.3 0.2)").in_gamut(ColorSpace::Rec2020));
+
+        for value in [
+            "color(display-p3 1 0 0)",
+            "oklch(0.7 0.4 150)",
+            "lab(50 120 -120)",
+        ] {
+            let mapped = Color::new(value).to_srgb().unwrap();
+            assert!(mapped.in_gamut(ColorSpace::Srgb), "Map \"{}\"", value);
+
+            // Gamut mapping keeps the lightness and the hue
+            let (original, mapped) = (
+                Color::new(value).to_oklch().unwrap().data,
+                mapped.to_oklch().unwrap().data,
+            );
+            match (original, mapped) {
+                (
+                    ColorData::OKLCH { l, h, .. },
+                    ColorData::OKLCH {
+                        l: mapped_l,
+                        h: mapped_h,
+                        ..
+                    },
+                ) => {
+                    assert!((l - mapped_l).abs() < 0.02, "Map \"{}\"", value);
+                    assert!((h - mapped_h).abs() < 5.0, "Map \"{}\"", value);
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        assert_close(
+            Color::new("oklch(1.2 0.3 100)").to_srgb(),
+            "rgb(255 255 255)",
+            0.01,
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Perceptual distance between colors.
+
+use super::conversion::{distance, xyz_to_lab, xyz_to_oklab, Coords};
+use super::Color;
+
+use std::f64::consts::PI;
+
+impl Color {
+    /// CIEDE2000 color difference, about 1 is the smallest difference people notice.
+    ///
+    /// Alpha is ignored. Returns `None` if one of the colors is not a valid color.
+    pub fn delta_e(&self, other: &Color) -> Option<f32> {
+        let (a, _) = self.data.to_xyz()?;
+        let (b, _) = other.data.to_xyz()?;
+
+        Some(ciede2000(xyz_to_lab(a), xyz_to_lab(b)) as f32)
+    }
+
+    /// Euclidean distance in OKLab, about 0.02 is the smallest difference people notice.
+    ///
+    /// Alpha is ignored. Returns `None` if one of the colors is not a valid color.
+    pub fn delta_e_ok(&self, other: &Color) -> Option<f32> {
+        let (a, _) = self.data.to_xyz()?;
+        let (b, _) = other.data.to_xyz()?;
+
+        Some(distance(xyz_to_oklab(a), xyz_to_oklab(b)) as f32)
+    }
+}
+
+/// CIEDE2000 difference of two Lab colors with the parametric factors set to 1
+fn ciede2000([l1, a1, b1]: Coords, [l2, a2, b2]: Coords) -> f64 {
+    let c_mean = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (c_mean.powi(7) / (c_mean.powi(7) + 25f64.powi(7))).sqrt());
+
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |a: f64, b: f64| match a == 0.0 && b == 0.0 {
+        true => 0.0,
+        false => b.atan2(a).rem_euclid(2.0 * PI),
+    };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+    let delta_l = l2 - l1;
+    let delta_c = c2 - c1;
+    let delta_h = match c1 * c2 == 0.0 {
+        true => 0.0,
+        false => {
+            let delta = h2 - h1;
+            if delta > PI {
+                delta - 2.0 * PI
+            } else if delta < -PI {
+                delta + 2.0 * PI
+            } else {
+                delta
+            }
+        }
+    };
+    let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).sin();
+
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= PI {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 2.0 * PI {
+        (h1 + h2 + 2.0 * PI) / 2.0
+    } else {
+        (h1 + h2 - 2.0 * PI) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_mean - 30f64.to_radians()).cos()
+        + 0.24 * (2.0 * h_mean).cos()
+        + 0.32 * (3.0 * h_mean + 6f64.to_radians()).cos()
+        - 0.20 * (4.0 * h_mean - 63f64.to_radians()).cos();
+    let delta_theta = 30f64.to_radians() * (-((h_mean.to_degrees() - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_mean.powi(7) / (c_mean.powi(7) + 25f64.powi(7))).sqrt();
+    let s_l = 1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_mean;
+    let s_h = 1.0 + 0.015 * c_mean * t;
+    let r_t = -(2.0 * delta_theta).sin() * r_c;
+
+    ((delta_l / s_l).powi(2)
+        + (delta_c / s_c).powi(2)
+        + (delta_h / s_h).powi(2)
+        + r_t * (delta_c / s_c) * (delta_h / s_h))
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ciede2000_reference_pairs() {
+        // Pairs from Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference Formula"
+        let tests = [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, -1.3802, -84.2814], [50.0, 0.0, -82.7485], 1.0),
+            ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+            ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+            (
+                [60.2574, -34.0099, 36.2677],
+                [60.4626, -34.1751, 39.4387],
+                1.2644,
+            ),
+            (
+                [22.7233, 20.0904, -46.694],
+                [23.0331, 14.973, -42.5619],
+                2.0373,
+            ),
+            ([2.0776, 0.0795, -1.135], [0.9033, -0.0636, -0.5514], 0.9082),
+        ];
+
+        for (a, b, expected) in tests {
+            let delta_e = ciede2000(a, b);
+            assert!(
+                (delta_e - expected).abs() < 0.0001,
+                "ΔE {:?} {:?} = {}",
+                a,
+                b,
+                delta_e
+            );
+            assert!((ciede2000(b, a) - expected).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn delta_e() {
+        let gray = Color::new("#333");
+
+        assert_eq!(gray.delta_e(&Color::new("rgb(51 51 51)")), Some(0.));
+        assert!(gray.delta_e(&Color::new("#343434")).unwrap() < 1.);
+        assert!(gray.delta_e(&Color::new("#fff")).unwrap() > 50.);
+        assert!(gray.delta_e_ok(&Color::new("#343434")).unwrap() < 0.02);
+        assert!(gray.delta_e_ok(&Color::new("#3f3f3f")).unwrap() > 0.02);
+        assert_eq!(gray.delta_e(&Color::new("none")), None);
+        assert_eq!(Color::new("none").delta_e_ok(&gray), None);
+    }
+}
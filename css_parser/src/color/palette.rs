@@ -0,0 +1,90 @@
+//! Closest colors of the named color table or of a design palette.
+
+use super::{Color, NAMED_COLORS};
+
+impl Color {
+    /// Named color with the smallest CIEDE2000 difference, and the difference,
+    /// e.g. `red` and 0.21 for `#fe0000`.
+    ///
+    /// Alpha is ignored. Returns `None` if the color is not a valid color.
+    pub fn nearest_named(&self) -> Option<(&'static str, f32)> {
+        NAMED_COLORS
+            .iter()
+            .filter_map(|(name, rgb)| Some((*name, self.delta_e(&Color::named(name, *rgb))?)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Index of the palette color with the smallest CIEDE2000 difference, and
+    /// the difference. When several colors are equally close the first one wins.
+    ///
+    /// Alpha is ignored. Returns `None` if the color is not a valid color or
+    /// the palette has no valid colors.
+    pub fn snap_to_palette(&self, palette: &[Color]) -> Option<(usize, f32)> {
+        palette
+            .iter()
+            .enumerate()
+            .filter_map(|(index, color)| Some((index, self.delta_e(color)?)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_named() {
+        let tests = [
+            ("#fe0000", "red"),
+            ("red", "red"),
+            ("#ff6348", "tomato"),
+            ("rgb(0 0 0 / 0.5)", "black"),
+            ("hsl(270 50% 40%)", "rebeccapurple"),
+            ("#fefefe", "white"),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(
+                Color::new(value).nearest_named().map(|(name, _)| name),
+                Some(expected),
+                "nearest_named {}",
+                value
+            );
+        }
+
+        let (_, delta_e) = Color::new("red").nearest_named().unwrap();
+        assert_eq!(delta_e, 0.);
+        let (_, delta_e) = Color::new("#fe0000").nearest_named().unwrap();
+        assert!((delta_e - 0.21).abs() < 0.01, "{}", delta_e);
+        assert_eq!(Color::new("none").nearest_named(), None);
+    }
+
+    #[test]
+    fn snap_to_palette() {
+        let palette: Vec<Color> = ["#ef4444", "#3b82f6", "#22c55e", "#ef4444"]
+            .iter()
+            .map(|value| Color::new(value))
+            .collect();
+
+        let tests = [
+            ("#fe0000", Some(0)),
+            ("#ef4444", Some(0)),
+            ("#3b83f6", Some(1)),
+            ("green", Some(2)),
+            ("none", None),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(
+                Color::new(value)
+                    .snap_to_palette(&palette)
+                    .map(|(index, _)| index),
+                expected,
+                "snap_to_palette {}",
+                value
+            );
+        }
+
+        assert_eq!(Color::new("red").snap_to_palette(&[]), None);
+    }
+}
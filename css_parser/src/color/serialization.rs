@@ -0,0 +1,185 @@
+//! Writing colors back to css in a chosen format.
+
+use super::{Color, ColorData, NAMED_COLORS};
+
+/// Output format of `Color::to_css`
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ColorFormat {
+    /// Shortest hex form, e.g. #f00 or #ff000080
+    ShortHex,
+    /// Example: #ff0000, or #ff000080 with alpha
+    Hex,
+    /// Example: rgb(255 0 0 / 0.5)
+    Rgb,
+    /// Example: rgb(255, 0, 0), or rgba(255, 0, 0, 0.5) with alpha
+    RgbLegacy,
+    /// Example: hsl(0 100% 50%)
+    Hsl,
+    /// Example: oklch(0.628 0.2577 29.23)
+    Oklch,
+    /// Closest named color, e.g. red for #fe0000. Only opaque colors have one.
+    Named,
+    /// Shortest of the short hex form and a named color with exactly the same
+    /// value, e.g. red for rgb(255, 0, 0) and #f00 for #ff0000
+    Minified,
+}
+
+impl Color {
+    /// Color written in `format`. Colors outside of the sRGB gamut are gamut
+    /// mapped for the sRGB based formats.
+    ///
+    /// Returns `None` if the color is not a valid color, or for a translucent
+    /// color in the `Named` format.
+    pub fn to_css(&self, format: ColorFormat) -> Option<String> {
+        let (r, g, b, alpha) = match self.to_srgb()?.data {
+            ColorData::RGBA { r, g, b, a } => (channel(r), channel(g), channel(b), a),
+            _ => return None,
+        };
+        let opaque = opaque(alpha);
+
+        let css = match format {
+            ColorFormat::ShortHex => {
+                let hex = hex(r, g, b, alpha);
+                let bytes = hex.as_bytes();
+                match bytes[1..].chunks(2).all(|pair| pair[0] == pair[1]) {
+                    true => {
+                        let short: String = hex[1..].chars().step_by(2).collect();
+                        format!("#{}", short)
+                    }
+                    false => hex,
+                }
+            }
+            ColorFormat::Hex => hex(r, g, b, alpha),
+            ColorFormat::Rgb if opaque => format!("rgb({} {} {})", r, g, b),
+            ColorFormat::Rgb => format!("rgb({} {} {} / {})", r, g, b, number(alpha, 3)),
+            ColorFormat::RgbLegacy if opaque => format!("rgb({}, {}, {})", r, g, b),
+            ColorFormat::RgbLegacy => {
+                format!("rgba({}, {}, {}, {})", r, g, b, number(alpha, 3))
+            }
+            ColorFormat::Hsl => match self.to_hsl()?.data {
+                ColorData::HSLA { h, s, l, .. } => with_alpha(
+                    format!("hsl({} {}% {}%", number(h, 2), number(s, 2), number(l, 2)),
+                    alpha,
+                ),
+                _ => return None,
+            },
+            ColorFormat::Oklch => match self.to_oklch()?.data {
+                ColorData::OKLCH { l, c, h, .. } => with_alpha(
+                    format!("oklch({} {} {}", number(l, 4), number(c, 4), number(h, 2)),
+                    alpha,
+                ),
+                _ => return None,
+            },
+            ColorFormat::Named if opaque => self.nearest_named()?.0.to_string(),
+            ColorFormat::Named => return None,
+            ColorFormat::Minified => {
+                let hex = self.to_css(ColorFormat::ShortHex)?;
+                let named = NAMED_COLORS
+                    .iter()
+                    .find(|(_, rgb)| opaque && *rgb == [r, g, b])
+                    .map(|(name, _)| name);
+
+                match named {
+                    Some(name) if name.len() < hex.len() => name.to_string(),
+                    _ => hex,
+                }
+            }
+        };
+
+        Some(css)
+    }
+}
+
+/// Channel from 0 to 255 rounded to an integer
+fn channel(value: f32) -> u8 {
+    value.round().clamp(0., 255.) as u8
+}
+
+/// Whether alpha rounds to 255 as a byte, so the color is written without alpha
+fn opaque(alpha: f32) -> bool {
+    channel(alpha * 255.) == 255
+}
+
+fn hex(r: u8, g: u8, b: u8, alpha: f32) -> String {
+    match opaque(alpha) {
+        true => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        false => format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, channel(alpha * 255.)),
+    }
+}
+
+/// Closes a color function opened in `css`, with alpha after a slash if it is not 1
+fn with_alpha(css: String, alpha: f32) -> String {
+    match opaque(alpha) {
+        true => format!("{})", css),
+        false => format!("{} / {})", css, number(alpha, 3)),
+    }
+}
+
+/// Number rounded to `decimals` without trailing zeros
+fn number(value: f32, decimals: i32) -> String {
+    let scale = 10f32.powi(decimals);
+    let rounded = (value * scale).round() / scale;
+
+    match rounded == 0. {
+        true => "0".to_string(),
+        false => format!("{}", rounded),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_css() {
+        let tests = [
+            ("rgb(255, 0, 0)", ColorFormat::ShortHex, "#f00"),
+            ("#ff000080", ColorFormat::ShortHex, "#ff000080"),
+            ("rgb(255 0 0 / 0.2)", ColorFormat::ShortHex, "#f003"),
+            ("#abc", ColorFormat::Hex, "#aabbcc"),
+            ("red", ColorFormat::Hex, "#ff0000"),
+            ("hsl(0 100% 50% / 50%)", ColorFormat::Hex, "#ff000080"),
+            ("#f00", ColorFormat::Rgb, "rgb(255 0 0)"),
+            ("#ff000080", ColorFormat::Rgb, "rgb(255 0 0 / 0.502)"),
+            ("#f00", ColorFormat::RgbLegacy, "rgb(255, 0, 0)"),
+            (
+                "hsla(120, 100%, 25%, .5)",
+                ColorFormat::RgbLegacy,
+                "rgba(0, 128, 0, 0.5)",
+            ),
+            ("#f00", ColorFormat::Hsl, "hsl(0 100% 50%)"),
+            ("#336699", ColorFormat::Hsl, "hsl(210 50% 40%)"),
+            (
+                "rgb(0 0 255 / 0.25)",
+                ColorFormat::Hsl,
+                "hsl(240 100% 50% / 0.25)",
+            ),
+            ("#f00", ColorFormat::Oklch, "oklch(0.628 0.2577 29.23)"),
+            ("#fff", ColorFormat::Oklch, "oklch(1 0 0)"),
+            ("#fe0000", ColorFormat::Named, "red"),
+            ("#ff6348", ColorFormat::Named, "tomato"),
+            ("rgb(255,0,0)", ColorFormat::Minified, "red"),
+            ("#ff0000", ColorFormat::Minified, "red"),
+            ("#ffffff", ColorFormat::Minified, "#fff"),
+            ("rgb(210 180 140)", ColorFormat::Minified, "tan"),
+            ("#663399", ColorFormat::Minified, "#639"),
+            ("#fe0000", ColorFormat::Minified, "#fe0000"),
+            ("rgb(255 0 0 / 0.999)", ColorFormat::ShortHex, "#f00"),
+            ("rgb(255 0 0 / 0.999)", ColorFormat::Rgb, "rgb(255 0 0)"),
+            ("rgb(255 0 0 / 0.999)", ColorFormat::Minified, "red"),
+        ];
+
+        for (value, format, expected) in tests {
+            assert_eq!(
+                Color::new(value).to_css(format).as_deref(),
+                Some(expected),
+                "to_css {} as {:?}",
+                value,
+                format
+            );
+        }
+
+        assert_eq!(Color::new("#ff000080").to_css(ColorFormat::Named), None);
+        assert_eq!(Color::new("none").to_css(ColorFormat::Hex), None);
+    }
+}
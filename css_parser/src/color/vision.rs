@@ -0,0 +1,122 @@
+//! Simulation of color vision deficiencies.
+
+use super::conversion::{linear_to_srgb, multiply, srgb_to_linear, Matrix};
+use super::{Color, ColorData};
+
+use std::fmt;
+
+/// Dichromacy, where one of the three kinds of cones is missing
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Deficiency {
+    /// No red cones
+    Protanopia,
+    /// No green cones
+    Deuteranopia,
+    /// No blue cones
+    Tritanopia,
+}
+
+/// Simulation matrices in linear sRGB of Machado, Oliveira and Fernandes,
+/// "A Physiologically-based Model for Simulation of Color Vision Deficiency",
+/// with severity 1
+const PROTANOPIA: Matrix = [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+];
+const DEUTERANOPIA: Matrix = [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.01182, 0.04294, 0.968881],
+];
+const TRITANOPIA: Matrix = [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.3039],
+];
+
+impl Deficiency {
+    pub const ALL: [Deficiency; 3] = [
+        Deficiency::Protanopia,
+        Deficiency::Deuteranopia,
+        Deficiency::Tritanopia,
+    ];
+
+    fn matrix(&self) -> Matrix {
+        match self {
+            Deficiency::Protanopia => PROTANOPIA,
+            Deficiency::Deuteranopia => DEUTERANOPIA,
+            Deficiency::Tritanopia => TRITANOPIA,
+        }
+    }
+}
+
+impl Color {
+    /// Color as seen with `deficiency`, as an rgb() color with the same alpha
+    pub fn simulate(&self, deficiency: Deficiency) -> Option<Color> {
+        let (r, g, b, a) = match self.to_srgb()?.data {
+            ColorData::RGBA { r, g, b, a } => (r, g, b, a),
+            _ => return None,
+        };
+        let linear = [r, g, b].map(|c| srgb_to_linear(c as f64 / 255.0));
+        let [r, g, b] = multiply(deficiency.matrix(), linear)
+            .map(|c| (linear_to_srgb(c.clamp(0.0, 1.0)) * 255.0) as f32);
+
+        Some(Color::from_data(ColorData::RGBA { r, g, b, a }))
+    }
+}
+
+impl fmt::Display for Deficiency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Deficiency::Protanopia => write!(f, "protanopia"),
+            Deficiency::Deuteranopia => write!(f, "deuteranopia"),
+            Deficiency::Tritanopia => write!(f, "tritanopia"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulate() {
+        // Grays look the same with every deficiency
+        for value in ["#000", "#777", "#fff"] {
+            let color = Color::new(value);
+
+            for deficiency in Deficiency::ALL {
+                let simulated = color.simulate(deficiency).unwrap();
+                assert!(
+                    color.delta_e(&simulated).unwrap() < 0.5,
+                    "Simulate {} \"{}\": \"{}\"",
+                    deficiency,
+                    value,
+                    simulated
+                );
+            }
+        }
+
+        // Red and green are hard to tell apart without red or green cones,
+        // but easy without blue cones
+        let (red, green) = (Color::new("#b33"), Color::new("#663"));
+        let delta_e = |deficiency| {
+            red.simulate(deficiency)
+                .unwrap()
+                .delta_e(&green.simulate(deficiency).unwrap())
+                .unwrap()
+        };
+        assert!(red.delta_e(&green).unwrap() > 30.);
+        assert!(delta_e(Deficiency::Protanopia) < 10.);
+        assert!(delta_e(Deficiency::Deuteranopia) < 10.);
+        assert!(delta_e(Deficiency::Tritanopia) > 20.);
+
+        assert_eq!(
+            Color::new("rgb(255 0 0 / 0.5)")
+                .simulate(Deficiency::Deuteranopia)
+                .map(|color| color.original.ends_with("/ 0.5)")),
+            Some(true)
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use crate::parser::parse_value;
+use crate::structs::{Declaration, Stylesheet, Value};
+use crate::tokenizer::{Token, Tokenizer};
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+/// Longest value a substitution may produce, in bytes. Longer values are
+/// invalid at computed-value time, so nested references such as
+/// `--b: var(--a) var(--a)` can't grow exponentially.
+pub const MAX_SUBSTITUTION_LENGTH: usize = 1 << 16;
+
+/// A `var()` function found in a value
+#[derive(PartialEq, Debug)]
+pub struct VarReference {
+    /// Example: --brand-primary
+    pub name: String,
+    /// Source text after the first comma, `None` when there is no comma
+    pub fallback: Option<String>,
+    /// Byte offset of `var(` in the value
+    pub start: usize,
+    /// Byte offset after the closing `)`
+    pub end: usize,
+}
+
+/// Finds `var()` functions in a value. Functions nested in a fallback are not
+/// included, they are found when the fallback itself is searched.
+///
+/// Returns `None` if one of the functions is malformed, e.g. `var(red)`.
+pub fn var_references(value: &str) -> Option<Vec<VarReference>> {
+    let mut tokenizer = Tokenizer::new(value);
+    let mut tokens = Vec::new();
+
+    while let Some(token) = tokenizer.next_token() {
+        tokens.push((token, tokenizer.token_start(), tokenizer.position()));
+    }
+
+    let mut references = Vec::new();
+    let mut index = 0;
+
+    while index < tokens.len() {
+        match tokens[index].0 {
+            Token::Function(ref name) if name.eq_ignore_ascii_case("var") => {
+                let close = closing_paren(&tokens, index);
+                let arguments = &tokens[index + 1..close];
+                let mut arguments = arguments.iter().filter(|t| t.0 != Token::Whitespace);
+
+                let name = match arguments.next() {
+                    Some((Token::Ident(name), _, _)) if name.starts_with("--") => name.clone(),
+                    _ => return None,
+                };
+                let fallback = match arguments.next() {
+                    Some((Token::Comma, _, comma_end)) => {
+                        let fallback_end = match tokens.get(close) {
+                            Some((_, start, _)) => *start,
+                            None => value.len(),
+                        };
+                        Some(value[*comma_end..fallback_end].trim().to_string())
+                    }
+                    None => None,
+                    Some(_) => return None,
+                };
+
+                references.push(VarReference {
+                    name,
+                    fallback,
+                    start: tokens[index].1,
+                    end: tokens.get(close).map_or(value.len(), |t| t.2),
+                });
+                index = close + 1;
+            }
+            _ => index += 1,
+        }
+    }
+
+    Some(references)
+}
+
+/// Index of the `)` closing the function at `open`, or `tokens.len()` if it is not closed
+fn closing_paren(tokens: &[(Token, usize, usize)], open: usize) -> usize {
+    let mut depth = 0;
+
+    for (index, (token, _, _)) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::Function(_) | Token::OpenParen => depth += 1,
+            Token::CloseParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return index;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tokens.len()
+}
+
+/// Substitutes `var()` functions with custom properties declared in a stylesheet.
+///
+/// Selectors and the cascade are not taken into account: every custom property
+/// is global and the last declaration wins.
+pub struct VarResolver {
+    properties: HashMap<String, String>,
+    /// Custom properties that depend on themselves, they are invalid
+    cyclic: HashSet<String>,
+    /// Substituted values of the custom properties resolved so far
+    resolved: RefCell<HashMap<String, Option<String>>>,
+}
+
+impl VarResolver {
+    pub fn new(stylesheet: &Stylesheet) -> VarResolver {
+        let mut properties = HashMap::new();
+
+        for rule in stylesheet.style_rules() {
+            for declaration in &rule.declarations {
+                if declaration.property.starts_with("--") {
+                    properties.insert(declaration.property.clone(), declaration.raw_value.clone());
+                }
+            }
+        }
+
+        let cyclic = find_cycles(&properties);
+
+        VarResolver {
+            properties,
+            cyclic,
+            resolved: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Value of a custom property with every `var()` substituted.
+    ///
+    /// Returns `None` if the property is not declared, is part of a dependency
+    /// cycle, references an invalid property without a fallback or is longer
+    /// than `MAX_SUBSTITUTION_LENGTH` after substitution.
+    pub fn property(&self, name: &str) -> Option<String> {
+        if self.cyclic.contains(name) {
+            return None;
+        }
+        if let Some(resolved) = self.resolved.borrow().get(name) {
+            return resolved.clone();
+        }
+
+        let resolved = self.resolve(self.properties.get(name)?);
+        self.resolved
+            .borrow_mut()
+            .insert(name.to_string(), resolved.clone());
+
+        resolved
+    }
+
+    /// Substitutes every `var()` in a value.
+    ///
+    /// Returns `None` if the value is invalid at computed-value time, e.g. it
+    /// references an undeclared property without a fallback, or the result is
+    /// longer than `MAX_SUBSTITUTION_LENGTH`.
+    pub fn resolve(&self, value: &str) -> Option<String> {
+        let mut result = String::new();
+        let mut last_end = 0;
+
+        for reference in var_references(value)? {
+            let substitution = match self.property(&reference.name) {
+                Some(substitution) => substitution,
+                None => self.resolve(reference.fallback.as_ref()?)?,
+            };
+
+            result.push_str(&value[last_end..reference.start]);
+            result.push_str(&substitution);
+            last_end = reference.end;
+
+            if result.len() > MAX_SUBSTITUTION_LENGTH {
+                return None;
+            }
+        }
+        result.push_str(&value[last_end..]);
+
+        match result.len() > MAX_SUBSTITUTION_LENGTH {
+            true => None,
+            false => Some(result),
+        }
+    }
+
+    /// Typed value of a declaration after `var()` substitution
+    pub fn resolve_declaration(&self, declaration: &Declaration) -> Option<Value> {
+        Some(parse_value(
+            &declaration.property,
+            &self.resolve(&declaration.raw_value)?,
+        ))
+    }
+}
+
+/// Custom properties which reference themselves directly or through other properties
+fn find_cycles(properties: &HashMap<String, String>) -> HashSet<String> {
+    let mut dependencies: HashMap<&str, Vec<String>> = HashMap::new();
+
+    for (name, value) in properties {
+        let mut names = Vec::new();
+        collect_dependencies(value, &mut names);
+        dependencies.insert(name, names);
+    }
+
+    let mut cyclic = HashSet::new();
+    for name in properties.keys() {
+        let mut visited = HashSet::new();
+        let mut stack: Vec<&str> = dependencies[name.as_str()]
+            .iter()
+            .map(String::as_str)
+            .collect();
+
+        while let Some(next) = stack.pop() {
+            if next == name {
+                cyclic.insert(name.clone());
+                break;
+            }
+            if visited.insert(next) {
+                if let Some(names) = dependencies.get(next) {
+                    stack.extend(names.iter().map(String::as_str));
+                }
+            }
+        }
+    }
+
+    cyclic
+}
+
+/// Names of all properties referenced in a value, including the ones in fallbacks
+fn collect_dependencies(value: &str, names: &mut Vec<String>) {
+    for reference in var_references(value).unwrap_or_default() {
+        if let Some(ref fallback) = reference.fallback {
+            collect_dependencies(fallback, names);
+        }
+        names.push(reference.name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse;
+
+    #[test]
+    fn find_var_references() {
+        assert_eq!(
+            var_references("1px solid VAR( --a ,var(--b, red) )"),
+            Some(vec![VarReference {
+                name: "--a".to_string(),
+                fallback: Some("var(--b, red)".to_string()),
+                start: 10,
+                end: 35,
+            }])
+        );
+        assert_eq!(
+            var_references("calc(var(--a) * 2)"),
+            Some(vec![VarReference {
+                name: "--a".to_string(),
+                fallback: None,
+                start: 5,
+                end: 13,
+            }])
+        );
+        assert_eq!(
+            var_references("var(--a,)").unwrap()[0].fallback,
+            Some("".to_string())
+        );
+        assert_eq!(var_references("var(red)"), None);
+        assert_eq!(var_references("var(--a red)"), None);
+    }
+
+    #[test]
+    fn resolve() {
+        let (stylesheet, _) = parse(
+            ":root { --brand: #0af; --border: 1px solid var(--brand); --Case: Red; } \
+             @media print { :root { --brand: black; } }",
+        );
+        let resolver = VarResolver::new(&stylesheet);
+
+        assert_eq!(resolver.property("--brand"), Some("black".to_string()));
+        assert_eq!(
+            resolver.property("--border"),
+            Some("1px solid black".to_string())
+        );
+        assert_eq!(resolver.property("--case"), None);
+        assert_eq!(resolver.property("--Case"), Some("Red".to_string()));
+        assert_eq!(
+            resolver.resolve("var(--missing, var(--brand))"),
+            Some("black".to_string())
+        );
+        assert_eq!(resolver.resolve("var(--missing)"), None);
+        assert_eq!(resolver.resolve("0 0 var(--a,)"), Some("0 0 ".to_string()));
+    }
+
+    #[test]
+    fn resolve_cycles() {
+        let (stylesheet, _) = parse(
+            "a { --a: var(--b, red); --b: var(--a); --c: var(--c, blue); \
+             --d: var(--a, green); --e: var(--d) }",
+        );
+        let resolver = VarResolver::new(&stylesheet);
+
+        assert_eq!(resolver.property("--a"), None);
+        assert_eq!(resolver.property("--b"), None);
+        assert_eq!(resolver.property("--c"), None);
+        assert_eq!(resolver.property("--d"), Some("green".to_string()));
+        assert_eq!(resolver.property("--e"), Some("green".to_string()));
+    }
+
+    #[test]
+    fn resolve_exponential_growth() {
+        let mut css = String::from("a { --p0: x x x x;");
+        for i in 1..20 {
+            let reference = format!("var(--p{})", i - 1);
+            css.push_str(&format!(
+                " --p{}: {};",
+                i,
+                [reference.as_str(); 4].join(" ")
+            ));
+        }
+        css.push_str(" }");
+        let (stylesheet, _) = parse(&css);
+        let resolver = VarResolver::new(&stylesheet);
+
+        assert_eq!(resolver.property("--p1").map(|v| v.len()), Some(31));
+        assert_eq!(resolver.property("--p19"), None);
+        assert_eq!(resolver.resolve("var(--p19, red)"), Some("red".to_string()));
+    }
+
+    #[test]
+    fn resolve_declaration() {
+        let (stylesheet, _) =
+            parse(":root { --brand-primary: #0af } a { color: var(--brand-primary, red) }");
+        let resolver = VarResolver::new(&stylesheet);
+        let declaration = &stylesheet.style_rules()[1].declarations[0];
+
+        assert_eq!(
+            format!("{}", resolver.resolve_declaration(declaration).unwrap()),
+            "#0af"
+        );
+    }
+}
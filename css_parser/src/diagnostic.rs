@@ -0,0 +1,145 @@
+use crate::span::Span;
+
+use std::fmt;
+
+/// Problem found while parsing. The parser recovers from every problem
+/// and keeps going, so diagnostics are collected next to the stylesheet.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    pub message: String,
+    pub span: Span,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Severity {
+    /// Invalid css, the affected part is dropped
+    Error,
+    /// Valid syntax with a value the analyzer could not understand
+    Warning,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum DiagnosticCode {
+    /// Example: "text<newline>
+    BadString,
+    /// Example: url(a b)
+    BadUrl,
+    /// Input ended inside a rule
+    UnexpectedEof,
+    /// Example: a stray } on the top level
+    UnexpectedToken,
+    /// Example: color red;
+    InvalidDeclaration,
+    /// Example: color: ;
+    EmptyValue,
+    /// Example: color: rgb(a, b, c);
+    InvalidColor,
+    /// Example: an @media inside a declaration block
+    UnsupportedAtRule,
+    /// Example: a:nth-child(foo)
+    InvalidSelector,
+    /// Example: width: calc(1px + 1deg)
+    InvalidValue,
+}
+
+impl Diagnostic {
+    pub fn new(
+        severity: Severity,
+        code: DiagnosticCode,
+        message: String,
+        span: Span,
+    ) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code,
+            message,
+            span,
+        }
+    }
+
+    pub fn error(code: DiagnosticCode, message: String, span: Span) -> Diagnostic {
+        Diagnostic::new(Severity::Error, code, message, span)
+    }
+
+    pub fn warning(code: DiagnosticCode, message: String, span: Span) -> Diagnostic {
+        Diagnostic::new(Severity::Warning, code, message, span)
+    }
+
+    /// Diagnostic message followed by the source line it points to, if any
+    pub fn render(&self, source: &str) -> String {
+        match self.span.snippet(source) {
+            Some(snippet) => format!("{}\n{}", self, snippet),
+            None => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            self.severity, self.code, self.span, self.message
+        )
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiagnosticCode::BadString => write!(f, "bad-string"),
+            DiagnosticCode::BadUrl => write!(f, "bad-url"),
+            DiagnosticCode::UnexpectedEof => write!(f, "unexpected-eof"),
+            DiagnosticCode::UnexpectedToken => write!(f, "unexpected-token"),
+            DiagnosticCode::InvalidDeclaration => write!(f, "invalid-declaration"),
+            DiagnosticCode::EmptyValue => write!(f, "empty-value"),
+            DiagnosticCode::InvalidColor => write!(f, "invalid-color"),
+            DiagnosticCode::UnsupportedAtRule => write!(f, "unsupported-at-rule"),
+            DiagnosticCode::InvalidSelector => write!(f, "invalid-selector"),
+            DiagnosticCode::InvalidValue => write!(f, "invalid-value"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::span::LineIndex;
+
+    #[test]
+    fn render() {
+        let source = "a {\n  color: rgb(a);\n}";
+        let diagnostic = Diagnostic::warning(
+            DiagnosticCode::InvalidColor,
+            "invalid color \"rgb(a)\"".to_string(),
+            LineIndex::new(source).span(source, 13, 19),
+        );
+
+        assert_eq!(
+            diagnostic.render(source),
+            "warning[invalid-color] 2:10: invalid color \"rgb(a)\"\n2 |   color: rgb(a);\n  |          ^^^^^^"
+        );
+
+        let diagnostic = Diagnostic::warning(
+            DiagnosticCode::InvalidColor,
+            "invalid color \"rgb(a)\"".to_string(),
+            Span::default(),
+        );
+        assert_eq!(
+            diagnostic.render(source),
+            "warning[invalid-color] 0:0: invalid color \"rgb(a)\""
+        );
+    }
+}
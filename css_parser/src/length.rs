@@ -0,0 +1,134 @@
+use crate::structs::Unit;
+
+/// Sizes which relative lengths are resolved against.
+///
+/// Font metrics are not known to the analyzer, so `ex`, `ch`, `cap` and `ic`
+/// use the fallbacks of CSS Values 4 (0.5em, 0.5em, 0.7em and 1em). The
+/// writing mode is horizontal, so inline units follow the width and block
+/// units the height, and there is one viewport size for the small, large
+/// and dynamic viewport units.
+#[derive(PartialEq, Clone, Debug)]
+pub struct LengthContext {
+    /// Font size of the root element, for rem
+    pub root_font_size: f32,
+    /// Font size of the element, for em
+    pub font_size: f32,
+    /// Line height of the root element, for rlh
+    pub root_line_height: f32,
+    /// Line height of the element, for lh
+    pub line_height: f32,
+    pub viewport_width: f32,
+    pub viewport_height: f32,
+    /// Size of the query container for cq* units, `None` uses the viewport
+    /// as when there is no container
+    pub container_size: Option<(f32, f32)>,
+    /// Size percentages are resolved against, e.g. the width of the containing block
+    pub percentage_basis: Option<f32>,
+}
+
+impl Default for LengthContext {
+    fn default() -> Self {
+        LengthContext {
+            root_font_size: 16.0,
+            font_size: 16.0,
+            root_line_height: 19.2,
+            line_height: 19.2,
+            viewport_width: 1920.0,
+            viewport_height: 1080.0,
+            container_size: None,
+            percentage_basis: None,
+        }
+    }
+}
+
+impl LengthContext {
+    /// Length in px, `None` if the unit is not a length this context can resolve
+    pub fn to_px(&self, value: f32, unit: &Unit) -> Option<f32> {
+        if let Some((factor, Unit::Px)) = unit.canonical() {
+            return Some(value * factor);
+        }
+
+        let (width, height) = (self.viewport_width, self.viewport_height);
+        let (container_width, container_height) = self.container_size.unwrap_or((width, height));
+        let px = match unit {
+            Unit::Em => value * self.font_size,
+            Unit::Rem => value * self.root_font_size,
+            Unit::Ex | Unit::Ch => value * self.font_size * 0.5,
+            Unit::Rex | Unit::Rch => value * self.root_font_size * 0.5,
+            Unit::Cap => value * self.font_size * 0.7,
+            Unit::Rcap => value * self.root_font_size * 0.7,
+            Unit::Ic => value * self.font_size,
+            Unit::Ric => value * self.root_font_size,
+            Unit::Lh => value * self.line_height,
+            Unit::Rlh => value * self.root_line_height,
+            Unit::Vw
+            | Unit::Vi
+            | Unit::Svw
+            | Unit::Svi
+            | Unit::Lvw
+            | Unit::Lvi
+            | Unit::Dvw
+            | Unit::Dvi => value * width / 100.0,
+            Unit::Vh
+            | Unit::Vb
+            | Unit::Svh
+            | Unit::Svb
+            | Unit::Lvh
+            | Unit::Lvb
+            | Unit::Dvh
+            | Unit::Dvb => value * height / 100.0,
+            Unit::Vmin | Unit::Svmin | Unit::Lvmin | Unit::Dvmin => {
+                value * width.min(height) / 100.0
+            }
+            Unit::Vmax | Unit::Svmax | Unit::Lvmax | Unit::Dvmax => {
+                value * width.max(height) / 100.0
+            }
+            Unit::Cqw | Unit::Cqi => value * container_width / 100.0,
+            Unit::Cqh | Unit::Cqb => value * container_height / 100.0,
+            Unit::Cqmin => value * container_width.min(container_height) / 100.0,
+            Unit::Cqmax => value * container_width.max(container_height) / 100.0,
+            Unit::Pct => value * self.percentage_basis? / 100.0,
+            _ => return None,
+        };
+
+        Some(px)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_px() {
+        let context = LengthContext {
+            font_size: 20.0,
+            percentage_basis: Some(400.0),
+            ..Default::default()
+        };
+        let tests = [
+            (1.0, Unit::In, Some(96.0)),
+            (2.0, Unit::Em, Some(40.0)),
+            (2.0, Unit::Rem, Some(32.0)),
+            (10.0, Unit::Vw, Some(192.0)),
+            (10.0, Unit::Vmin, Some(108.0)),
+            (2.0, Unit::Ch, Some(20.0)),
+            (1.0, Unit::Lh, Some(19.2)),
+            (50.0, Unit::Dvh, Some(540.0)),
+            (10.0, Unit::Cqi, Some(192.0)),
+            (25.0, Unit::Pct, Some(100.0)),
+            (1.0, Unit::Deg, None),
+            (1.0, Unit::Fr, None),
+        ];
+
+        for (value, unit, expected) in tests {
+            assert_eq!(
+                context.to_px(value, &unit),
+                expected,
+                "to_px {}{}",
+                value,
+                unit
+            );
+        }
+    }
+}
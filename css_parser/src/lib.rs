@@ -1,4 +1,12 @@
+pub mod analyzer;
+pub mod calc;
 pub mod color;
+pub mod custom_properties;
+pub mod diagnostic;
+pub mod length;
 pub mod parser;
 pub mod selector;
+pub mod shorthand;
+pub mod span;
 pub mod structs;
+pub mod tokenizer;
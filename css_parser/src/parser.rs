@@ -1,297 +1,1493 @@
-use std::iter::{Iterator, Peekable};
-use std::str::Chars;
-
-use crate::color::Color;
-use crate::selector::{Selector, SimpleSelector};
-use crate::structs::{Declaration, Rule, Stylesheet, Unit, Value};
+use crate::calc::{Calc, MathFunction, Operator};
+use crate::color::{Color, ColorData};
+use crate::custom_properties::var_references;
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+use crate::selector::{
+    AttributeMatcher, AttributeOperator, AttributeSelector, CaseSensitivity, Combinator,
+    PseudoClass, PseudoClassArgument, PseudoElement, Selector, SelectorComponent, SimpleSelector,
+};
+use crate::span::{LineIndex, Span};
+use crate::structs::{
+    AtRule, ConditionalRule, CssRule, Declaration, FontFaceRule, ImportRule, Keyframe,
+    KeyframesRule, Number, Rule, Separator, Stylesheet, Unit, Value,
+};
+use crate::tokenizer::{tokenize, HashType, NumberType, Token, Tokenizer};
 
 pub struct CssParser<'a> {
-    chars: Peekable<Chars<'a>>,
+    input: &'a str,
+    tokens: Vec<Token>,
+    /// Byte range of every token in `input`
+    bounds: Vec<(usize, usize)>,
+    lines: LineIndex,
+    index: usize,
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl<'a> CssParser<'a> {
-    pub fn new(full_css: &str) -> CssParser {
-        CssParser {
-            chars: full_css.chars().peekable(),
+    pub fn new(full_css: &'a str) -> CssParser<'a> {
+        let mut tokenizer = Tokenizer::new(full_css);
+        let mut tokens = Vec::new();
+        let mut bounds = Vec::new();
+
+        while let Some(token) = tokenizer.next_token() {
+            bounds.push((tokenizer.token_start(), tokenizer.position()));
+            tokens.push(token);
+        }
+
+        let mut parser = CssParser {
+            input: full_css,
+            tokens,
+            bounds,
+            lines: LineIndex::new(full_css),
+            index: 0,
+            diagnostics: Vec::new(),
+        };
+
+        for index in 0..parser.tokens.len() {
+            let (code, message) = match parser.tokens[index] {
+                Token::BadString => (DiagnosticCode::BadString, "unterminated string"),
+                Token::BadUrl => (DiagnosticCode::BadUrl, "invalid characters in url"),
+                _ => continue,
+            };
+            let span = parser.span((index, index + 1));
+
+            parser.error(code, message.to_string(), span);
         }
+
+        parser
     }
 
     pub fn parse_stylesheet(&mut self) -> Stylesheet {
-        let mut stylesheet = Stylesheet::default();
+        Stylesheet::new(self.parse_rules(true))
+    }
+
+    /// Problems found so far, in the order they were found
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
 
-        while self.chars.peek().is_some() {
-            self.consume_while(char::is_whitespace);
-            self.consume_comments();
+    /// Consumes a list of rules. A nested list ends with the `}` of its block.
+    fn parse_rules(&mut self, top_level: bool) -> Vec<CssRule> {
+        let mut rules = Vec::new();
 
-            let selectors = self.parse_selectors();
-            let styles = self.parse_declarations();
-            let rule = Rule::new(selectors, styles);
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Whitespace => self.index += 1,
+                Token::Cdo | Token::Cdc if top_level => self.index += 1,
+                Token::CloseCurly => {
+                    self.index += 1;
 
-            stylesheet.rules.push(rule);
+                    if !top_level {
+                        return rules;
+                    }
+                    let span = self.span((self.index - 1, self.index));
+                    self.error(
+                        DiagnosticCode::UnexpectedToken,
+                        "unexpected `}`".to_string(),
+                        span,
+                    );
+                }
+                Token::AtKeyword(_) => rules.push(self.parse_at_rule()),
+                _ => {
+                    if let Some(rule) = self.parse_rule() {
+                        rules.push(CssRule::Style(rule));
+                    }
+                }
+            }
         }
 
-        stylesheet
+        if !top_level {
+            self.unexpected_eof();
+        }
+
+        rules
     }
 
-    fn consume_comments(&mut self) {
-        let start = {
-            if self.chars.peek() == Some(&'/') {
-                self.chars.next();
+    /// Consumes a qualified rule. Returns `None` if there is no block after the prelude,
+    /// the prelude is empty or one of the selectors is invalid, which invalidates
+    /// the whole rule.
+    fn parse_rule(&mut self) -> Option<Rule> {
+        let start = self.index;
+        let first_diagnostic = self.diagnostics.len();
+        let selectors = self.parse_selectors();
+
+        match self.peek() {
+            Some(Token::OpenCurly) => {}
+            Some(_) => {
+                let span = self.span((start, self.index));
+                self.error(
+                    DiagnosticCode::UnexpectedToken,
+                    "expected `{` after selectors".to_string(),
+                    span,
+                );
+                return None;
+            }
+            None => {
+                self.unexpected_eof();
+                return None;
             }
+        }
+        let invalid = self.diagnostics[first_diagnostic..]
+            .iter()
+            .any(|diagnostic| diagnostic.code == DiagnosticCode::InvalidSelector);
+        if selectors.is_empty() && !invalid {
+            let span = self.span((self.index, self.index + 1));
+            self.error(
+                DiagnosticCode::InvalidSelector,
+                "expected a selector before `{`".to_string(),
+                span,
+            );
+        }
+        if invalid || selectors.is_empty() {
+            self.consume_component_value();
+            return None;
+        }
+        self.index += 1;
+
+        let mut rule = Rule::new(selectors, self.parse_declarations());
+        rule.span = self.span((start, self.index));
 
-            self.chars.peek() == Some(&'*')
+        Some(rule)
+    }
+
+    fn parse_at_rule(&mut self) -> CssRule {
+        let start = self.index;
+        let name = match self.peek() {
+            Some(Token::AtKeyword(name)) => name.to_lowercase(),
+            _ => String::new(),
         };
+        self.index += 1;
 
-        if start {
-            let mut stop = false;
+        let prelude = self.consume_until(|t| {
+            *t == Token::Semicolon || *t == Token::OpenCurly || *t == Token::CloseCurly
+        });
 
-            while !stop {
-                self.chars.next();
+        let has_block = match self.peek() {
+            Some(Token::OpenCurly) => true,
+            Some(Token::Semicolon) => {
+                self.index += 1;
+                false
+            }
+            _ => false,
+        };
 
-                stop = {
-                    if self.chars.peek() == Some(&'*') {
-                        self.chars.next();
-                    }
+        match name.as_ref() {
+            "media" | "supports" if has_block => {
+                self.index += 1;
+                let mut rule = ConditionalRule::new(self.text(prelude), self.parse_rules(false));
+                rule.span = self.span((start, self.index));
 
-                    self.chars.next() == Some('/')
+                match name.as_ref() {
+                    "media" => CssRule::Media(rule),
+                    _ => CssRule::Supports(rule),
+                }
+            }
+            "font-face" if has_block => {
+                self.index += 1;
+                let declarations = self.parse_declarations();
+
+                CssRule::FontFace(FontFaceRule {
+                    declarations,
+                    span: self.span((start, self.index)),
+                })
+            }
+            "keyframes" | "-webkit-keyframes" | "-moz-keyframes" | "-o-keyframes" if has_block => {
+                self.index += 1;
+                let vendor_prefix = match name.trim_end_matches("keyframes") {
+                    "" => None,
+                    prefix => Some(prefix.to_string()),
                 };
 
-                if stop {
-                    self.consume_while(char::is_whitespace);
+                let keyframes = self.parse_keyframes();
+
+                CssRule::Keyframes(KeyframesRule {
+                    vendor_prefix,
+                    name: self.text(prelude),
+                    keyframes,
+                    span: self.span((start, self.index)),
+                })
+            }
+            "import" if !has_block => {
+                let mut rule = self.parse_import(prelude);
+                rule.span = self.span((start, self.index));
+
+                CssRule::Import(rule)
+            }
+            _ => {
+                let block = match has_block {
+                    true => Some(self.consume_block_text()),
+                    false => None,
+                };
 
-                    if self.chars.peek() == Some(&'/') {
-                        self.consume_comments()
+                CssRule::Other(AtRule {
+                    name,
+                    prelude: self.text(prelude),
+                    block,
+                    span: self.span((start, self.index)),
+                })
+            }
+        }
+    }
+
+    /// Splits the prelude of @import into the url and the conditions after it.
+    fn parse_import(&self, (start, end): (usize, usize)) -> ImportRule {
+        let (url, conditions_start) = match (self.tokens.get(start), self.tokens.get(start + 1)) {
+            (Some(Token::Url(url)), _) | (Some(Token::QuotedString(url)), _) => {
+                (url.clone(), start + 1)
+            }
+            (Some(Token::Function(name)), Some(Token::QuotedString(url)))
+                if name.eq_ignore_ascii_case("url") =>
+            {
+                let mut index = start + 2;
+                while index < end && self.tokens[index] != Token::CloseParen {
+                    index += 1;
+                }
+                (url.clone(), index + 1)
+            }
+            _ => (String::new(), start),
+        };
+
+        let mut conditions_start = conditions_start.min(end);
+        while conditions_start < end && self.tokens[conditions_start] == Token::Whitespace {
+            conditions_start += 1;
+        }
+
+        ImportRule {
+            url,
+            conditions: self.text((conditions_start, end)),
+            span: Span::default(),
+        }
+    }
+
+    /// Consumes the content of a @keyframes block, including the closing `}`.
+    fn parse_keyframes(&mut self) -> Vec<Keyframe> {
+        let mut keyframes = Vec::new();
+
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Whitespace => self.index += 1,
+                Token::CloseCurly => {
+                    self.index += 1;
+                    return keyframes;
+                }
+                _ => {
+                    let start = self.index;
+                    let prelude =
+                        self.consume_until(|t| *t == Token::OpenCurly || *t == Token::CloseCurly);
+
+                    if self.peek() == Some(&Token::OpenCurly) {
+                        self.index += 1;
+                        let declarations = self.parse_declarations();
+
+                        keyframes.push(Keyframe {
+                            selectors: self
+                                .text(prelude)
+                                .split(',')
+                                .map(|s| s.trim().to_lowercase())
+                                .collect(),
+                            declarations,
+                            span: self.span((start, self.index)),
+                        });
                     }
                 }
             }
         }
+
+        self.unexpected_eof();
+        keyframes
     }
 
     fn parse_selectors(&mut self) -> Vec<Selector> {
         let mut selectors = Vec::new();
 
-        while self.chars.peek().map_or(false, |c| *c != '{') {
+        while self
+            .peek()
+            .is_some_and(|t| *t != Token::OpenCurly && *t != Token::CloseCurly)
+        {
             let selector = self.parse_selector();
 
-            if selector != Selector::default() {
+            if !selector.simple.is_empty() {
                 selectors.push(selector);
             }
 
-            self.consume_while(char::is_whitespace);
-            if self.chars.peek().map_or(false, |c| *c == ',') {
-                self.chars.next();
+            self.consume_whitespace();
+            match self.peek() {
+                Some(Token::Comma) => self.index += 1,
+                Some(Token::CloseParen) => {
+                    let span = self.span((self.index, self.index + 1));
+                    self.error(
+                        DiagnosticCode::InvalidSelector,
+                        "unexpected `)` in selector".to_string(),
+                        span,
+                    );
+                    self.index += 1;
+                }
+                _ => {}
             }
         }
 
-        self.chars.next();
         selectors
     }
 
+    /// Consumes a selector list inside a functional pseudo-class, up to the closing `)`
+    fn parse_nested_selectors(&mut self) -> Vec<Selector> {
+        let mut selectors = Vec::new();
+
+        loop {
+            let selector = self.parse_selector();
+
+            if !selector.simple.is_empty() {
+                selectors.push(selector);
+            }
+
+            self.consume_whitespace();
+            match self.peek() {
+                Some(Token::Comma) => self.index += 1,
+                _ => return selectors,
+            }
+        }
+    }
+
     fn parse_selector(&mut self) -> Selector {
-        let mut s_selector = SimpleSelector::default();
         let mut selector = Selector::default();
 
-        self.consume_while(char::is_whitespace);
+        self.consume_whitespace();
+        let start = self.index;
+
+        loop {
+            let s_selector = self.parse_simple_selector();
+
+            if s_selector == SimpleSelector::default() {
+                break;
+            }
+            selector.simple.push(s_selector);
 
-        s_selector.tag_name = match self.chars.peek() {
-            Some(&c) if is_valid_start_ident(c) => Some(self.parse_identifier()),
+            let whitespace_before = self.peek() == Some(&Token::Whitespace);
+            self.consume_whitespace();
+
+            let combinator = match self.peek() {
+                _ if self.at_selector_end() => break,
+                Some(Token::Delim('>')) => Combinator::Child,
+                Some(Token::Delim('+')) => Combinator::NextSibling,
+                Some(Token::Delim('~')) => Combinator::SubsequentSibling,
+                Some(_) if whitespace_before => Combinator::Descendant,
+                Some(_) | None => break,
+            };
+            if combinator != Combinator::Descendant {
+                self.index += 1;
+                self.consume_whitespace();
+            }
+            selector.combinators.push(combinator);
+        }
+
+        if !self.at_selector_end() {
+            let rest = self.index;
+            while !self.at_selector_end() {
+                self.consume_component_value();
+            }
+            let span = self.span((rest, self.index));
+
+            self.error(
+                DiagnosticCode::InvalidSelector,
+                format!(
+                    "unsupported selector \"{}\"",
+                    self.text((rest, self.index)).trim_end()
+                ),
+                span,
+            );
+        }
+
+        if selector.combinators.len() >= selector.simple.len() {
+            selector
+                .combinators
+                .truncate(selector.simple.len().saturating_sub(1));
+        }
+
+        let mut end = self.index;
+        while end > start && self.tokens[end - 1] == Token::Whitespace {
+            end -= 1;
+        }
+        selector.span = self.span((start, end));
+
+        selector
+    }
+
+    fn at_selector_end(&self) -> bool {
+        matches!(
+            self.peek(),
+            Some(Token::Comma)
+                | Some(Token::OpenCurly)
+                | Some(Token::CloseCurly)
+                | Some(Token::CloseParen)
+                | None
+        )
+    }
+
+    /// Consumes a compound selector, e.g. `a#page.wrapper[href]:hover::before`
+    fn parse_simple_selector(&mut self) -> SimpleSelector {
+        let mut components = Vec::new();
+
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                components.push(SelectorComponent::Type(name.to_lowercase()));
+                self.index += 1;
+            }
+            Some(Token::Delim('*')) => {
+                components.push(SelectorComponent::Universal);
+                self.index += 1;
+            }
+            _ => {}
+        }
+
+        loop {
+            let has_pseudo_element = components
+                .iter()
+                .any(|c| matches!(c, SelectorComponent::PseudoElement(_)));
+
+            match self.peek() {
+                Some(Token::Hash(id, HashType::Id)) => {
+                    components.push(SelectorComponent::Id(id.clone()));
+                    self.index += 1;
+                }
+                Some(Token::Delim('.')) => match self.tokens.get(self.index + 1) {
+                    Some(Token::Ident(class_name)) => {
+                        components.push(SelectorComponent::Class(class_name.clone()));
+                        self.index += 2;
+                    }
+                    _ => break,
+                },
+                Some(Token::OpenSquare) => match self.parse_attribute_selector() {
+                    Some(attribute) => components.push(SelectorComponent::Attribute(attribute)),
+                    None => break,
+                },
+                // User action pseudo-classes may follow a pseudo-element, e.g. ::before:hover,
+                // another pseudo-element may not
+                Some(Token::Colon) => match self.tokens.get(self.index + 1) {
+                    Some(Token::Colon) if has_pseudo_element => break,
+                    Some(Token::Ident(name))
+                        if has_pseudo_element && is_legacy_pseudo_element(name) =>
+                    {
+                        break
+                    }
+                    Some(Token::Colon) => {
+                        self.index += 2;
+                        match self.parse_pseudo_element() {
+                            Some(pseudo_element) => {
+                                components.push(SelectorComponent::PseudoElement(pseudo_element))
+                            }
+                            None => {
+                                self.index -= 2;
+                                break;
+                            }
+                        }
+                    }
+                    Some(Token::Ident(name)) if is_legacy_pseudo_element(name) => {
+                        self.index += 1;
+                        if let Some(pseudo_element) = self.parse_pseudo_element() {
+                            components.push(SelectorComponent::PseudoElement(pseudo_element));
+                        }
+                    }
+                    _ => {
+                        self.index += 1;
+                        match self.parse_pseudo_class() {
+                            Some(pseudo_class) => {
+                                components.push(SelectorComponent::PseudoClass(pseudo_class))
+                            }
+                            None => {
+                                self.index -= 1;
+                                break;
+                            }
+                        }
+                    }
+                },
+                _ => break,
+            }
+        }
+
+        SimpleSelector { components }
+    }
+
+    /// Consumes `[name]` or `[name op value flag]`.
+    /// Nothing is consumed if the attribute selector is invalid.
+    fn parse_attribute_selector(&mut self) -> Option<AttributeSelector> {
+        let checkpoint = self.index;
+        let attribute = self.parse_attribute_selector_content();
+
+        if attribute.is_none() {
+            self.index = checkpoint;
+        }
+
+        attribute
+    }
+
+    fn parse_attribute_selector_content(&mut self) -> Option<AttributeSelector> {
+        self.index += 1;
+        self.consume_whitespace();
+
+        let name = match self.peek() {
+            Some(Token::Ident(name)) => name.to_lowercase(),
+            _ => return None,
+        };
+        self.index += 1;
+        self.consume_whitespace();
+
+        let operator = match (self.peek(), self.tokens.get(self.index + 1)) {
+            (Some(Token::CloseSquare), _) => {
+                self.index += 1;
+                return Some(AttributeSelector {
+                    name,
+                    matcher: None,
+                });
+            }
+            (Some(Token::Delim('=')), _) => {
+                self.index += 1;
+                AttributeOperator::Equals
+            }
+            (Some(Token::Delim(c)), Some(Token::Delim('='))) => {
+                let operator = match c {
+                    '~' => AttributeOperator::Includes,
+                    '|' => AttributeOperator::DashMatch,
+                    '^' => AttributeOperator::Prefix,
+                    '$' => AttributeOperator::Suffix,
+                    '*' => AttributeOperator::Substring,
+                    _ => return None,
+                };
+                self.index += 2;
+                operator
+            }
+            _ => return None,
+        };
+        self.consume_whitespace();
+
+        let value = match self.peek() {
+            Some(Token::Ident(value)) | Some(Token::QuotedString(value)) => value.clone(),
+            _ => return None,
+        };
+        self.index += 1;
+        self.consume_whitespace();
+
+        let case_sensitivity = match self.peek() {
+            Some(Token::Ident(flag)) if flag.eq_ignore_ascii_case("i") => {
+                Some(CaseSensitivity::Insensitive)
+            }
+            Some(Token::Ident(flag)) if flag.eq_ignore_ascii_case("s") => {
+                Some(CaseSensitivity::Sensitive)
+            }
             _ => None,
         };
+        if case_sensitivity.is_some() {
+            self.index += 1;
+            self.consume_whitespace();
+        }
 
-        let mut multiple_ids = false;
-        while self
-            .chars
-            .peek()
-            .map_or(false, |c| *c != ',' && *c != '{' && !(*c).is_whitespace())
+        if self.peek() != Some(&Token::CloseSquare) {
+            return None;
+        }
+        self.index += 1;
+
+        Some(AttributeSelector {
+            name,
+            matcher: Some(AttributeMatcher {
+                operator,
+                value,
+                case_sensitivity,
+            }),
+        })
+    }
+
+    /// Consumes a pseudo-class after its `:`
+    fn parse_pseudo_class(&mut self) -> Option<PseudoClass> {
+        let name = match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.to_lowercase();
+                self.index += 1;
+                return Some(PseudoClass {
+                    name,
+                    argument: None,
+                });
+            }
+            Some(Token::Function(name)) => name.to_lowercase(),
+            _ => return None,
+        };
+        let start = self.index;
+        self.index += 1;
+
+        let (known, argument) = match name.as_str() {
+            "not" | "is" | "where" | "matches" | "-webkit-any" | "-moz-any" | "host"
+            | "host-context" => (
+                true,
+                Some(PseudoClassArgument::Selectors(
+                    self.parse_nested_selectors(),
+                )),
+            ),
+            "has" => (
+                true,
+                Some(PseudoClassArgument::Relative(
+                    self.parse_relative_selectors(),
+                )),
+            ),
+            "nth-child" | "nth-last-child" | "nth-of-type" | "nth-last-of-type" | "nth-col"
+            | "nth-last-col" => (true, self.parse_nth()),
+            _ => (false, None),
+        };
+
+        // Unknown argument, or a known one that is invalid
+        if argument.is_none() || self.peek() != Some(&Token::CloseParen) {
+            self.index = start;
+            self.consume_component_value();
+
+            let text = self.function_argument_text(start);
+            if known {
+                let span = self.span((start, self.index));
+                self.error(
+                    DiagnosticCode::InvalidSelector,
+                    format!("invalid argument \"{}\" of :{}()", text, name),
+                    span,
+                );
+            }
+
+            return Some(PseudoClass {
+                name,
+                argument: Some(PseudoClassArgument::Other(text)),
+            });
+        }
+        self.index += 1;
+
+        Some(PseudoClass { name, argument })
+    }
+
+    /// Consumes the argument of :has(), e.g. `> img, p`
+    fn parse_relative_selectors(&mut self) -> Vec<(Combinator, Selector)> {
+        let mut selectors = Vec::new();
+
+        loop {
+            self.consume_whitespace();
+
+            let combinator = match self.peek() {
+                Some(Token::Delim('>')) => Combinator::Child,
+                Some(Token::Delim('+')) => Combinator::NextSibling,
+                Some(Token::Delim('~')) => Combinator::SubsequentSibling,
+                _ => Combinator::Descendant,
+            };
+            if combinator != Combinator::Descendant {
+                self.index += 1;
+            }
+
+            let selector = self.parse_selector();
+            if !selector.simple.is_empty() {
+                selectors.push((combinator, selector));
+            }
+
+            self.consume_whitespace();
+            match self.peek() {
+                Some(Token::Comma) => self.index += 1,
+                _ => return selectors,
+            }
+        }
+    }
+
+    /// Consumes `An+B` with an optional `of <selectors>`, up to the closing `)`
+    fn parse_nth(&mut self) -> Option<PseudoClassArgument> {
+        let start = self.index;
+
+        while !matches!(self.peek(), Some(Token::CloseParen) | None)
+            && !matches!(self.peek(), Some(Token::Ident(of)) if of.eq_ignore_ascii_case("of"))
         {
-            match self.chars.peek() {
-                Some(&c) if c == '#' => {
-                    self.chars.next();
-                    if s_selector.id.is_some() || multiple_ids {
-                        s_selector.id = None;
-                        multiple_ids = true;
-                        self.parse_id();
-                    } else {
-                        s_selector.id = self.parse_id();
-                    }
+            self.index += 1;
+        }
+        let (a, b) = parse_an_plus_b(&self.text((start, self.index)))?;
+
+        let of = match self.peek() {
+            Some(Token::Ident(_)) => {
+                self.index += 1;
+                self.parse_nested_selectors()
+            }
+            _ => Vec::new(),
+        };
+
+        Some(PseudoClassArgument::Nth { a, b, of })
+    }
+
+    /// Consumes a pseudo-element after its `::`, or after `:` for legacy ones
+    fn parse_pseudo_element(&mut self) -> Option<PseudoElement> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.to_lowercase();
+                self.index += 1;
+                Some(PseudoElement {
+                    name,
+                    argument: None,
+                })
+            }
+            Some(Token::Function(name)) => {
+                let name = name.to_lowercase();
+                let start = self.index;
+                self.consume_component_value();
+                Some(PseudoElement {
+                    name,
+                    argument: Some(self.function_argument_text(start)),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Source text between `(` and `)` of a function consumed from `start`
+    fn function_argument_text(&self, start: usize) -> String {
+        let end = match self.tokens.get(self.index - 1) {
+            Some(Token::CloseParen) if self.index - 1 > start => self.index - 1,
+            _ => self.index,
+        };
+
+        self.text((start + 1, end)).trim().to_string()
+    }
+
+    /// Consumes the content of a declaration block, including the closing `}`.
+    fn parse_declarations(&mut self) -> Vec<Declaration> {
+        let mut declarations = Vec::<Declaration>::new();
+
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Whitespace | Token::Semicolon => self.index += 1,
+                Token::CloseCurly => {
+                    self.index += 1;
+                    return declarations;
                 }
-                Some(&c) if c == '.' => {
-                    self.chars.next();
-                    let class_name = self.parse_identifier();
+                Token::AtKeyword(name) => {
+                    let message = format!("@{} inside a declaration block is not supported", name);
+                    let start = self.index;
+                    self.parse_at_rule();
+                    let span = self.span((start, self.index));
 
-                    if class_name != String::from("") {
-                        s_selector.classes.push(class_name);
+                    self.warning(DiagnosticCode::UnsupportedAtRule, message, span);
+                }
+                Token::Ident(_) => {
+                    if let Some(declaration) = self.parse_declaration() {
+                        declarations.push(declaration);
                     }
                 }
                 _ => {
-                    self.consume_while(|c| c != ',' && c != '{');
+                    let range = self.consume_declaration_value();
+                    let span = self.span(range);
+
+                    self.error(
+                        DiagnosticCode::InvalidDeclaration,
+                        format!("expected a property name, found \"{}\"", self.text(range)),
+                        span,
+                    );
                 }
             }
         }
 
-        if s_selector != SimpleSelector::default() {
-            selector.simple.push(s_selector);
+        self.unexpected_eof();
+        declarations
+    }
+
+    fn parse_declaration(&mut self) -> Option<Declaration> {
+        let start = self.index;
+        let raw_property = match self.peek() {
+            Some(Token::Ident(name)) => name.clone(),
+            _ => return None,
+        };
+        let property = match raw_property.starts_with("--") {
+            true => raw_property.clone(),
+            false => raw_property.to_lowercase(),
+        };
+        self.index += 1;
+        self.consume_whitespace();
+
+        if self.peek() != Some(&Token::Colon) {
+            let range = (start, self.consume_declaration_value().1);
+            let span = self.span(range);
+
+            self.error(
+                DiagnosticCode::InvalidDeclaration,
+                format!("expected `:` after \"{}\"", property),
+                span,
+            );
+            return None;
+        }
+        self.index += 1;
+        let colon_end = self.index;
+
+        let full_range = self.consume_declaration_value();
+        let value_range = self.strip_important(full_range);
+        let important = value_range != full_range;
+        let raw_value = self.text(value_range);
+
+        if raw_value.is_empty() && !property.starts_with("--") {
+            let span = self.span((start, colon_end));
+            self.warning(
+                DiagnosticCode::EmptyValue,
+                format!("\"{}\" has an empty value", property),
+                span,
+            );
         }
 
-        selector
+        let value_enum = parse_value(&property, &raw_value);
+
+        if let Some(message) = calc_error(&value_enum) {
+            let span = self.span(value_range);
+            self.error(DiagnosticCode::InvalidValue, message, span);
+            return None;
+        }
+
+        let mut declaration = Declaration::new(property, value_enum);
+        declaration.raw_property = raw_property;
+        declaration.raw_value = raw_value;
+        declaration.important = important;
+        declaration.span = self.span((start, full_range.1.max(colon_end)));
+        declaration.value_span = self.span(value_range);
+
+        let values = match declaration.value {
+            Value::List { ref items, .. } if declaration.property == "border-color" => {
+                items.iter().collect()
+            }
+            ref value => vec![value],
+        };
+        for value in values {
+            if let Value::Color(Color {
+                data: ColorData::NONE,
+                ref original,
+                ..
+            }) = value
+            {
+                if !original.is_empty() {
+                    self.warning(
+                        DiagnosticCode::InvalidColor,
+                        format!("invalid color \"{}\"", original),
+                        declaration.value_span,
+                    );
+                }
+            }
+        }
+
+        Some(declaration)
+    }
+
+    /// Consumes component values up to the next `;` or the end of the block
+    /// and returns the range of consumed tokens without surrounding whitespace.
+    fn consume_declaration_value(&mut self) -> (usize, usize) {
+        self.consume_until(|t| *t == Token::Semicolon || *t == Token::CloseCurly)
+    }
+
+    /// Range of a declaration value without a trailing `!important`
+    fn strip_important(&self, (start, end): (usize, usize)) -> (usize, usize) {
+        let mut index = end;
+
+        match index.checked_sub(1).map(|i| &self.tokens[i]) {
+            Some(Token::Ident(name)) if index > start && name.eq_ignore_ascii_case("important") => {
+                index -= 1
+            }
+            _ => return (start, end),
+        }
+        while index > start && self.tokens[index - 1] == Token::Whitespace {
+            index -= 1;
+        }
+        if index == start || self.tokens[index - 1] != Token::Delim('!') {
+            return (start, end);
+        }
+        index -= 1;
+        while index > start && self.tokens[index - 1] == Token::Whitespace {
+            index -= 1;
+        }
+
+        (start, index)
+    }
+
+    /// Consumes component values until `is_end` matches a token outside of nested
+    /// blocks. Returns the range of consumed tokens without surrounding whitespace.
+    fn consume_until<F>(&mut self, is_end: F) -> (usize, usize)
+    where
+        F: Fn(&Token) -> bool,
+    {
+        self.consume_whitespace();
+
+        let start = self.index;
+        let mut end = self.index;
+
+        while let Some(token) = self.peek() {
+            match token {
+                token if is_end(token) => break,
+                Token::Whitespace => self.index += 1,
+                _ => {
+                    self.consume_component_value();
+                    end = self.index;
+                }
+            }
+        }
+
+        (start, end)
+    }
+
+    /// Consumes a `{}` block and returns the source text between the braces.
+    fn consume_block_text(&mut self) -> String {
+        let open = self.index;
+        self.consume_component_value();
+
+        let start = self.bounds[open].1;
+        let end = match self.tokens.get(self.index - 1) {
+            Some(Token::CloseCurly) if self.index - 1 > open => self.bounds[self.index - 1].0,
+            _ => self.input.len(),
+        };
+
+        self.input[start..end].to_string()
+    }
+
+    /// Location of a range of tokens
+    fn span(&self, (start, end): (usize, usize)) -> Span {
+        let start_offset = match self.bounds.get(start) {
+            Some(bounds) => bounds.0,
+            None => self.input.len(),
+        };
+        let end_offset = match end > start {
+            true => self.bounds[end - 1].1,
+            false => start_offset,
+        };
+
+        self.lines.span(self.input, start_offset, end_offset)
+    }
+
+    /// Source text of a range of tokens
+    fn text(&self, (start, end): (usize, usize)) -> String {
+        match end > start {
+            true => self.input[self.bounds[start].0..self.bounds[end - 1].1].to_string(),
+            false => String::new(),
+        }
+    }
+
+    /// Consumes a single token, or a whole block or function with its content.
+    fn consume_component_value(&mut self) {
+        let closing = match self.peek() {
+            Some(Token::OpenCurly) => Token::CloseCurly,
+            Some(Token::OpenSquare) => Token::CloseSquare,
+            Some(Token::OpenParen) | Some(Token::Function(_)) => Token::CloseParen,
+            Some(_) => {
+                self.index += 1;
+                return;
+            }
+            None => return,
+        };
+        self.index += 1;
+
+        while let Some(token) = self.peek() {
+            if *token == closing {
+                self.index += 1;
+                return;
+            }
+            self.consume_component_value();
+        }
+    }
+
+    fn error(&mut self, code: DiagnosticCode, message: String, span: Span) {
+        self.diagnostics
+            .push(Diagnostic::error(code, message, span));
+    }
+
+    fn warning(&mut self, code: DiagnosticCode, message: String, span: Span) {
+        self.diagnostics
+            .push(Diagnostic::warning(code, message, span));
+    }
+
+    fn unexpected_eof(&mut self) {
+        let span = self.span((self.tokens.len(), self.tokens.len()));
+
+        self.error(
+            DiagnosticCode::UnexpectedEof,
+            "unexpected end of input, expected `}`".to_string(),
+            span,
+        );
+    }
+
+    fn consume_whitespace(&mut self) {
+        while self.peek() == Some(&Token::Whitespace) {
+            self.index += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+}
+
+/// Pseudo-elements that can be written with a single colon, e.g. `:before`
+fn is_legacy_pseudo_element(name: &str) -> bool {
+    ["before", "after", "first-line", "first-letter"]
+        .iter()
+        .any(|legacy| legacy.eq_ignore_ascii_case(name))
+}
+
+/// Parses the `An+B` microsyntax, e.g. `2n+1`, `-n + 3`, `odd`
+fn parse_an_plus_b(text: &str) -> Option<(i32, i32)> {
+    let text: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+
+    match text.as_str() {
+        "odd" => return Some((2, 1)),
+        "even" => return Some((2, 0)),
+        _ => {}
+    }
+
+    match text.split_once('n') {
+        Some((a, b)) => {
+            let a = match a {
+                "" | "+" => 1,
+                "-" => -1,
+                a => a.parse().ok()?,
+            };
+            let b = match b {
+                "" => 0,
+                b if b.starts_with(['+', '-']) => b.parse().ok()?,
+                _ => return None,
+            };
+
+            Some((a, b))
+        }
+        None => Some((0, text.parse().ok()?)),
+    }
+}
+
+/// Typed value of a declaration, `value` is the source text without `!important`
+pub fn parse_value(property: &str, value: &str) -> Value {
+    if property.starts_with("--") {
+        return Value::Custom {
+            text: value.to_string(),
+            tokens: tokenize(value),
+        };
+    }
+
+    // Values with var() are only known after substitution, see `VarResolver`
+    if let Some(reference) = whole_var(value) {
+        return reference;
+    }
+    let color_property = matches!(property, "background-color" | "border-color" | "color");
+
+    match var_references(value) {
+        Some(references) if references.is_empty() => {}
+        Some(_) if !color_property => {}
+        _ => return Value::Other(value.to_string()),
+    }
+
+    match property {
+        // One to four colors, for the top, right, bottom and left borders
+        "border-color" => match parse_components(value) {
+            Value::List {
+                separator: Separator::Space,
+                items,
+            } => Value::List {
+                separator: Separator::Space,
+                items: items.into_iter().map(color).collect(),
+            },
+            value => color(value),
+        },
+        _ if color_property => Value::Color(Color::new(value)),
+        _ => parse_components(value),
+    }
+}
+
+/// Component value tree of a declaration value
+fn parse_components(value: &str) -> Value {
+    let mut tokenizer = Tokenizer::new(value);
+    let mut tokens = Vec::new();
+
+    while let Some(token) = tokenizer.next_token() {
+        tokens.push((token, tokenizer.token_start(), tokenizer.position()));
+    }
+
+    match tokens.is_empty() {
+        true => Value::Other(String::new()),
+        false => ValueParser {
+            value,
+            tokens,
+            index: 0,
+        }
+        .parse_list(false),
+    }
+}
+
+/// Component value as a color, `ColorData::NONE` if it is not one
+fn color(value: Value) -> Value {
+    match value {
+        Value::Color(_) => value,
+        value => Value::Color(Color::new(&value.to_string())),
+    }
+}
+
+/// Builds a component value tree from the tokens of a declaration value
+struct ValueParser<'a> {
+    value: &'a str,
+    /// Tokens with their byte range in `value`
+    tokens: Vec<(Token, usize, usize)>,
+    index: usize,
+}
+
+impl<'a> ValueParser<'a> {
+    /// Consumes values up to the end, or up to `)` if the list is `nested` in a function
+    fn parse_list(&mut self, nested: bool) -> Value {
+        let mut commas = Vec::new();
+        let mut slashes = Vec::new();
+        let mut spaces = Vec::new();
+
+        while let Some((token, _, _)) = self.tokens.get(self.index) {
+            match token {
+                Token::CloseParen if nested => break,
+                Token::Whitespace => self.index += 1,
+                Token::Comma => {
+                    self.index += 1;
+                    slashes.push(list(Separator::Space, std::mem::take(&mut spaces)));
+                    commas.push(list(Separator::Slash, std::mem::take(&mut slashes)));
+                }
+                Token::Delim('/') => {
+                    self.index += 1;
+                    slashes.push(list(Separator::Space, std::mem::take(&mut spaces)));
+                }
+                _ => {
+                    let component = self.parse_component();
+                    spaces.push(component);
+                }
+            }
+        }
+
+        if commas.is_empty() && slashes.is_empty() && spaces.is_empty() {
+            return list(Separator::Space, spaces);
+        }
+        slashes.push(list(Separator::Space, spaces));
+        commas.push(list(Separator::Slash, slashes));
+
+        list(Separator::Comma, commas)
     }
 
-    fn parse_identifier(&mut self) -> String {
-        let mut ident = String::new();
+    fn parse_component(&mut self) -> Value {
+        let (token, start, end) = self.tokens[self.index].clone();
+        self.index += 1;
 
-        match self.chars.peek() {
-            Some(&c) => {
-                if is_valid_start_ident(c) {
-                    ident.push_str(&self.consume_while(is_valid_ident))
+        match token {
+            Token::Ident(name) => Value::Keyword(name),
+            Token::Number(number, NumberType::Integer) => Value::Integer(number as i32),
+            Token::Number(number, NumberType::Number) => {
+                Value::Number(self.number(number, start, end))
+            }
+            Token::Percentage(number) => Value::Percentage(self.number(number, start, end)),
+            Token::Dimension(number, _, unit) => {
+                translate_length(self.number(number, start, end), &unit.to_lowercase())
+            }
+            Token::QuotedString(text) => Value::String(text),
+            Token::Url(url) => Value::Url(url),
+            Token::Hash(_, _) => {
+                let color = Color::new(&self.value[start..end]);
+
+                match color.data {
+                    ColorData::NONE => Value::Other(self.value[start..end].to_string()),
+                    _ => Value::Color(color),
                 }
             }
-            None => {}
+            Token::Function(name) => {
+                let name = name.to_lowercase();
+
+                if let Some(function) = MathFunction::new(&name) {
+                    let checkpoint = self.index;
+
+                    match self.parse_math_function(function) {
+                        Some(calc) => return Value::Calc(calc),
+                        None => self.index = checkpoint,
+                    }
+                }
+                let arguments = match self.parse_list(true) {
+                    Value::List {
+                        separator: Separator::Comma,
+                        items,
+                    } => items,
+                    Value::List {
+                        separator: Separator::Space,
+                        items,
+                    } if items.is_empty() => items,
+                    argument => vec![argument],
+                };
+                self.index += 1;
+
+                let end = self
+                    .tokens
+                    .get(self.index - 1)
+                    .map_or(self.value.len(), |t| t.2);
+                match name.as_str() {
+                    "rgb" | "rgba" | "hsl" | "hsla" | "hwb" | "lab" | "lch" | "oklab" | "oklch"
+                    | "color" => {
+                        let color = Color::new(&self.value[start..end]);
+
+                        if color.data != ColorData::NONE {
+                            return Value::Color(color);
+                        }
+                    }
+                    "var" => {
+                        if let Some(reference) = whole_var(&self.value[start..end]) {
+                            return reference;
+                        }
+                    }
+                    _ => {}
+                }
+
+                Value::Function { name, arguments }
+            }
+            Token::OpenParen | Token::OpenSquare | Token::OpenCurly => {
+                let closing = match token {
+                    Token::OpenParen => Token::CloseParen,
+                    Token::OpenSquare => Token::CloseSquare,
+                    _ => Token::CloseCurly,
+                };
+                let mut depth = 1;
+
+                while let Some((next, _, _)) = self.tokens.get(self.index) {
+                    self.index += 1;
+
+                    if *next == token
+                        || (closing == Token::CloseParen && matches!(next, Token::Function(_)))
+                    {
+                        depth += 1;
+                    } else if *next == closing {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                }
+
+                let end = self.tokens.get(self.index - 1).map_or(end, |t| t.2);
+                Value::Other(self.value[start..end].to_string())
+            }
+            _ => Value::Other(self.value[start..end].to_string()),
         }
-        ident.to_lowercase()
     }
 
-    fn parse_id(&mut self) -> Option<String> {
-        match &self.parse_identifier()[..] {
-            s if s.len() > 0 => Some(s.to_string()),
+    /// Arguments of calc(), min(), max() or clamp() after the function token,
+    /// `None` if they are not a valid math expression
+    fn parse_math_function(&mut self, function: MathFunction) -> Option<Calc> {
+        let mut arguments = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            arguments.push(self.parse_calc_sum()?);
+            self.skip_whitespace();
+
+            match self.tokens.get(self.index) {
+                Some((Token::Comma, _, _)) if function != MathFunction::Calc => self.index += 1,
+                Some((Token::CloseParen, _, _)) => {
+                    self.index += 1;
+                    break;
+                }
+                _ => return None,
+            }
+        }
+
+        match (function, arguments.len()) {
+            (MathFunction::Clamp, 3)
+            | (MathFunction::Calc | MathFunction::Min | MathFunction::Max, _) => {
+                Some(Calc::Function {
+                    function,
+                    arguments,
+                })
+            }
             _ => None,
         }
     }
 
-    fn parse_declarations(&mut self) -> Vec<Declaration> {
-        let mut declarations = Vec::<Declaration>::new();
+    /// `product [ ('+' | '-') product ]*`, `+` and `-` must be surrounded by whitespace
+    fn parse_calc_sum(&mut self) -> Option<Calc> {
+        let mut sum = self.parse_calc_product()?;
+
+        loop {
+            let checkpoint = self.index;
+
+            if !matches!(self.tokens.get(self.index), Some((Token::Whitespace, _, _))) {
+                break;
+            }
+            self.skip_whitespace();
+
+            let operator = match self.tokens.get(self.index) {
+                Some((Token::Delim('+'), _, _)) => Operator::Add,
+                Some((Token::Delim('-'), _, _)) => Operator::Subtract,
+                _ => {
+                    self.index = checkpoint;
+                    break;
+                }
+            };
+            self.index += 1;
 
-        while self.chars.peek().map_or(false, |c| *c != '}') {
-            self.consume_while(char::is_whitespace);
-            self.consume_comments();
+            if !matches!(self.tokens.get(self.index), Some((Token::Whitespace, _, _))) {
+                return None;
+            }
+            self.skip_whitespace();
+
+            sum = Calc::operation(sum, operator, self.parse_calc_product()?);
+        }
 
-            let property = self.consume_while(|x| x != ':').to_lowercase();
+        Some(sum)
+    }
 
-            self.chars.next();
-            self.consume_while(char::is_whitespace);
+    /// `value [ ('*' | '/') value ]*`
+    fn parse_calc_product(&mut self) -> Option<Calc> {
+        let mut product = self.parse_calc_value()?;
 
-            let value = self
-                .consume_while(|x| x != ';' && x != '\n' && x != '}')
-                .to_lowercase();
+        loop {
+            let checkpoint = self.index;
+            self.skip_whitespace();
 
-            let value_enum = match property.as_ref() {
-                "background-color" | "border-color" | "color" => {
-                    Value::Color(Color::new(value.as_ref()))
+            let operator = match self.tokens.get(self.index) {
+                Some((Token::Delim('*'), _, _)) => Operator::Multiply,
+                Some((Token::Delim('/'), _, _)) => Operator::Divide,
+                _ => {
+                    self.index = checkpoint;
+                    break;
                 }
-                "margin-right"
-                | "margin-bottom"
-                | "margin-left"
-                | "margin-top"
-                | "padding-right"
-                | "padding-bottom"
-                | "padding-left"
-                | "padding-top"
-                | "border-right-width"
-                | "border-bottom-width"
-                | "border-left-width"
-                | "border-top-width"
-                | "height"
-                | "width" => translate_length(&value),
-                _ => Value::Other(value),
             };
+            self.index += 1;
+            self.skip_whitespace();
+
+            product = Calc::operation(product, operator, self.parse_calc_value()?);
+        }
 
-            let declaration = Declaration::new(property, value_enum);
+        Some(product)
+    }
 
-            if self.chars.peek().map_or(false, |c| *c == ';') {
-                declarations.push(declaration);
-                self.chars.next();
-            } else {
-                self.consume_while(char::is_whitespace);
+    /// Number, dimension, constant, var(), nested math function or `( sum )`
+    fn parse_calc_value(&mut self) -> Option<Calc> {
+        match &self.tokens.get(self.index)?.0 {
+            Token::Number(_, _) | Token::Percentage(_) | Token::Dimension(_, _, _) => {
+                Some(Calc::value(self.parse_component()))
+            }
+            Token::Ident(name)
+                if ["e", "pi", "infinity", "-infinity", "nan"]
+                    .iter()
+                    .any(|constant| name.eq_ignore_ascii_case(constant)) =>
+            {
+                Some(Calc::value(self.parse_component()))
+            }
+            Token::Function(name) if MathFunction::new(&name.to_lowercase()).is_some() => {
+                let function = MathFunction::new(&name.to_lowercase())?;
+                self.index += 1;
 
-                if self.chars.peek().map_or(false, |c| *c == '}') {
-                    declarations.push(declaration);
+                self.parse_math_function(function)
+            }
+            Token::Function(name) if name.eq_ignore_ascii_case("var") => {
+                match self.parse_component() {
+                    value @ Value::Var { .. } => Some(Calc::value(value)),
+                    _ => None,
+                }
+            }
+            Token::OpenParen => {
+                self.index += 1;
+                self.skip_whitespace();
+                let sum = self.parse_calc_sum()?;
+                self.skip_whitespace();
+
+                match self.tokens.get(self.index) {
+                    Some((Token::CloseParen, _, _)) => {
+                        self.index += 1;
+                        Some(sum)
+                    }
+                    _ => None,
                 }
             }
-            self.consume_while(char::is_whitespace);
-            self.consume_comments();
+            _ => None,
         }
-
-        self.chars.next();
-        declarations
     }
 
-    fn consume_while<F>(&mut self, condition: F) -> String
-    where
-        F: Fn(char) -> bool,
-    {
-        let mut result = String::new();
-        while self.chars.peek().map_or(false, |c| condition(*c)) {
-            result.push(self.chars.next().unwrap());
+    fn skip_whitespace(&mut self) {
+        while let Some((Token::Whitespace, _, _)) = self.tokens.get(self.index) {
+            self.index += 1;
         }
+    }
 
-        result
+    /// Number with its representation taken from the source text of a numeric token
+    fn number(&self, value: f32, start: usize, end: usize) -> Number {
+        Number::new(value, numeric_prefix(&self.value[start..end]))
     }
 }
 
-fn translate_length(value: &str) -> Value {
-    let mut num_str = String::new();
-    let mut unit = String::new();
-    let mut parsing_num = true;
-
-    for c in value.chars() {
-        if (c.is_numeric() || c == '.') && parsing_num {
-            num_str.push(c);
-        } else {
-            unit.push(c);
-            parsing_num = false;
-        }
-    }
-
-    let number = num_str.parse().unwrap_or(0.0);
-
-    match unit.as_ref() {
-        "em" => Value::Length(number, Unit::Em),
-        "ex" => Value::Length(number, Unit::Ex),
-        "ch" => Value::Length(number, Unit::Ch),
-        "rem" => Value::Length(number, Unit::Rem),
-        "vh" => Value::Length(number, Unit::Vh),
-        "vw" => Value::Length(number, Unit::Vw),
-        "vmin" => Value::Length(number, Unit::Vmin),
-        "vmax" => Value::Length(number, Unit::Vmax),
-        "px" | "" => Value::Length(number, Unit::Px),
-        "mm" => Value::Length(number, Unit::Mm),
-        "q" => Value::Length(number, Unit::Q),
-        "cm" => Value::Length(number, Unit::Cm),
-        "in" => Value::Length(number, Unit::In),
-        "pt" => Value::Length(number, Unit::Pt),
-        "pc" => Value::Length(number, Unit::Pc),
-        "%" => Value::Length(number, Unit::Pct),
-        _ => Value::Length(number, Unit::Px),
+/// Leading `[+-]digits[.digits][e[+-]digits]` of a numeric token
+fn numeric_prefix(text: &str) -> &str {
+    let bytes = text.as_bytes();
+    let digits = |mut i: usize| {
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        i
+    };
+
+    let mut end = match bytes.first() {
+        Some(b'+') | Some(b'-') => 1,
+        _ => 0,
+    };
+    end = digits(end);
+    if bytes.get(end) == Some(&b'.') && bytes.get(end + 1).is_some_and(u8::is_ascii_digit) {
+        end = digits(end + 1);
+    }
+    if matches!(bytes.get(end), Some(b'e') | Some(b'E')) {
+        let exponent = match bytes.get(end + 1) {
+            Some(b'+') | Some(b'-') => end + 2,
+            _ => end + 1,
+        };
+        if bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+            end = digits(exponent);
+        }
     }
-}
 
-fn is_valid_ident(c: char) -> bool {
-    is_valid_start_ident(c) || c.is_digit(10) || c == '-'
+    &text[..end]
 }
 
-fn is_valid_start_ident(c: char) -> bool {
-    is_letter(c) || is_non_ascii(c) || c == '_'
+/// Type error of the first math function in `value` which mixes incompatible units
+fn calc_error(value: &Value) -> Option<String> {
+    match value {
+        Value::Calc(calc) => calc.calc_type().err(),
+        Value::Function { arguments, .. } => arguments.iter().find_map(calc_error),
+        Value::List { items, .. } => items.iter().find_map(calc_error),
+        _ => None,
+    }
 }
 
-fn is_letter(c: char) -> bool {
-    is_upper_letter(c) || is_lower_letter(c)
-}
+/// `Value::Var` if the whole `text` is one var() reference
+fn whole_var(text: &str) -> Option<Value> {
+    let mut references = var_references(text)?;
+
+    match references.len() == 1 && references[0].start == 0 && references[0].end == text.len() {
+        true => {
+            let reference = references.remove(0);
 
-fn is_upper_letter(c: char) -> bool {
-    c >= 'A' && c <= 'Z'
+            Some(Value::Var {
+                name: reference.name,
+                fallback: reference.fallback,
+            })
+        }
+        false => None,
+    }
 }
 
-fn is_lower_letter(c: char) -> bool {
-    c >= 'a' && c <= 'z'
+/// One value, or a list of `values` if there are several of them
+fn list(separator: Separator, mut values: Vec<Value>) -> Value {
+    match values.len() {
+        1 => values.remove(0),
+        _ => Value::List {
+            separator,
+            items: values,
+        },
+    }
 }
 
-fn is_non_ascii(c: char) -> bool {
-    c >= '\u{0080}'
+/// Value of a dimension, `unit` is lowercased
+fn translate_length(number: Number, unit: &str) -> Value {
+    match Unit::new(unit) {
+        unit if unit.is_length() => Value::Length(number, unit),
+        unit => Value::Dimension(number, unit),
+    }
 }
 
-pub fn parse(content: &str) -> Stylesheet {
-    let mut parser = CssParser::new(content.trim());
+pub fn parse(content: &str) -> (Stylesheet, Vec<Diagnostic>) {
+    let mut parser = CssParser::new(content);
+    let stylesheet = parser.parse_stylesheet();
 
-    parser.parse_stylesheet()
+    (stylesheet, parser.diagnostics)
 }
 
 pub fn stringify(styles: Stylesheet) -> String {
@@ -302,13 +1498,91 @@ pub fn stringify(styles: Stylesheet) -> String {
 mod tests {
     use super::*;
 
-    use crate::color::ColorData;
-    use std::fs::read_to_string;
+    use crate::diagnostic::Severity;
+    use std::fs::read_to_string;
+
+    /// Stylesheet with every span reset, to compare the same css laid out differently
+    /// or with nodes built by hand
+    fn without_spans(mut stylesheet: Stylesheet) -> Stylesheet {
+        clear_rule_spans(&mut stylesheet.rules);
+        stylesheet
+    }
+
+    fn selector_without_spans(mut selector: Selector) -> Selector {
+        clear_selector_spans(&mut selector);
+        selector
+    }
+
+    fn declarations_without_spans(mut declarations: Vec<Declaration>) -> Vec<Declaration> {
+        clear_declaration_spans(&mut declarations);
+        declarations
+    }
+
+    fn clear_rule_spans(rules: &mut [CssRule]) {
+        for rule in rules {
+            match rule {
+                CssRule::Style(rule) => {
+                    rule.span = Span::default();
+                    rule.selectors.iter_mut().for_each(clear_selector_spans);
+                    clear_declaration_spans(&mut rule.declarations);
+                }
+                CssRule::Media(rule) | CssRule::Supports(rule) => {
+                    rule.span = Span::default();
+                    clear_rule_spans(&mut rule.rules);
+                }
+                CssRule::Import(rule) => rule.span = Span::default(),
+                CssRule::FontFace(rule) => {
+                    rule.span = Span::default();
+                    clear_declaration_spans(&mut rule.declarations);
+                }
+                CssRule::Keyframes(rule) => {
+                    rule.span = Span::default();
+                    for keyframe in &mut rule.keyframes {
+                        keyframe.span = Span::default();
+                        clear_declaration_spans(&mut keyframe.declarations);
+                    }
+                }
+                CssRule::Other(rule) => rule.span = Span::default(),
+            }
+        }
+    }
+
+    fn clear_selector_spans(selector: &mut Selector) {
+        selector.span = Span::default();
+
+        for component in selector
+            .simple
+            .iter_mut()
+            .flat_map(|simple| &mut simple.components)
+        {
+            let pseudo_class = match component {
+                SelectorComponent::PseudoClass(pseudo_class) => pseudo_class,
+                _ => continue,
+            };
+            match &mut pseudo_class.argument {
+                Some(PseudoClassArgument::Selectors(selectors))
+                | Some(PseudoClassArgument::Nth { of: selectors, .. }) => {
+                    selectors.iter_mut().for_each(clear_selector_spans)
+                }
+                Some(PseudoClassArgument::Relative(selectors)) => selectors
+                    .iter_mut()
+                    .for_each(|(_, selector)| clear_selector_spans(selector)),
+                Some(PseudoClassArgument::Other(_)) | None => {}
+            }
+        }
+    }
+
+    fn clear_declaration_spans(declarations: &mut [Declaration]) {
+        for declaration in declarations {
+            declaration.span = Span::default();
+            declaration.value_span = Span::default();
+        }
+    }
 
     #[test]
     fn parse_string_empty() {
         assert_eq!(
-            parse(""),
+            parse("").0,
             Stylesheet { rules: vec![] },
             "parse empty string"
         );
@@ -317,19 +1591,17 @@ mod tests {
     #[test]
     fn parse_string_plain() {
         assert_eq!(
-            parse("body {color: red;}"),
+            without_spans(parse("body {color: red;}").0),
             Stylesheet {
-                rules: vec![Rule {
+                rules: vec![CssRule::Style(Rule {
                     selectors: vec![Selector {
-                        simple: vec![SimpleSelector {
-                            tag_name: Some("body".to_string()),
-                            id: None,
-                            classes: vec![]
-                        }],
+                        simple: vec![SimpleSelector::new(Some("body".to_string()), None, vec![])],
                         combinators: vec![],
+                        span: Span::default(),
                     }],
                     declarations: vec![Declaration {
                         property: "color".to_string(),
+                        raw_property: "color".to_string(),
                         value: Value::Color(Color {
                             original: "red".to_string(),
                             pattern: "#XXXXXX".to_string(),
@@ -339,9 +1611,14 @@ mod tests {
                                 b: 0.0,
                                 a: 1.0
                             }
-                        })
+                        }),
+                        raw_value: "red".to_string(),
+                        important: false,
+                        span: Span::default(),
+                        value_span: Span::default(),
                     }],
-                }]
+                    span: Span::default(),
+                })]
             },
             "parse plain css"
         );
@@ -353,22 +1630,20 @@ mod tests {
 
         let file_path = "../samples/plain.css";
 
-        let contents = read_to_string(&file_path).expect("Something went wrong reading the file");
+        let contents = read_to_string(file_path).expect("Something went wrong reading the file");
 
         assert_eq!(
             contents.trim(),
             stringify(Stylesheet {
-                rules: vec![Rule {
+                rules: vec![CssRule::Style(Rule {
                     selectors: vec![Selector {
-                        simple: vec![SimpleSelector {
-                            tag_name: Some("body".to_string()),
-                            id: None,
-                            classes: vec![]
-                        }],
+                        simple: vec![SimpleSelector::new(Some("body".to_string()), None, vec![])],
                         combinators: vec![],
+                        span: Span::default(),
                     }],
                     declarations: vec![Declaration {
                         property: "color".to_string(),
+                        raw_property: "color".to_string(),
                         value: Value::Color(Color {
                             original: "red".to_string(),
                             pattern: "#XXXXXX".to_string(),
@@ -378,9 +1653,14 @@ mod tests {
                                 b: 0.0,
                                 a: 1.0
                             }
-                        })
+                        }),
+                        raw_value: "red".to_string(),
+                        important: false,
+                        span: Span::default(),
+                        value_span: Span::default(),
                     }],
-                }]
+                    span: Span::default(),
+                })]
             })
             .trim(),
             "plain result"
@@ -389,41 +1669,36 @@ mod tests {
 
     #[test]
     fn parse_stringify_empty() {
-        assert_eq!("", stringify(parse("")), "empty result");
+        assert_eq!("", stringify(parse("").0), "empty result");
     }
 
     #[test]
     fn parse_stringify_plain_styles() {
         let file_path = "../samples/plain.css";
-        let contents = read_to_string(&file_path).expect("Something went wrong reading the file");
+        let contents = read_to_string(file_path).expect("Something went wrong reading the file");
 
-        assert_eq!(contents.trim(), stringify(parse(&contents)));
+        assert_eq!(contents.trim(), stringify(parse(&contents).0));
     }
 
     #[test]
     fn parse_stringify_ethalon_css_file() {
         let file_path = "../samples/ethalon.css";
-        let contents = read_to_string(&file_path).expect("Something went wrong reading the file");
+        let contents = read_to_string(file_path).expect("Something went wrong reading the file");
 
-        assert_eq!(contents.trim(), stringify(parse(&contents)));
+        assert_eq!(contents.trim(), stringify(parse(&contents).0));
     }
 
     #[test]
     fn parse_single_comment() {
-        let mut parser = CssParser::new("/**/ body {color: red;}");
-
-        parser.consume_comments();
-
-        assert_eq!("body {color: red;}", parser.chars.collect::<String>());
+        assert_eq!(
+            without_spans(parse("/**/ body {color: red;}").0),
+            without_spans(parse("body {color: red;}").0)
+        );
     }
 
     #[test]
     fn parse_only_single_comment() {
-        let mut parser = CssParser::new("/*  */ ");
-
-        parser.consume_comments();
-
-        assert_eq!(None, parser.chars.peek());
+        assert_eq!(parse("/*  */ "), (Stylesheet::default(), vec![]));
     }
 
     #[test]
@@ -435,7 +1710,7 @@ mod tests {
 
         assert_eq!(
             content_without_comments.trim(),
-            stringify(parse(&content_with_comments))
+            stringify(parse(&content_with_comments).0)
         );
     }
 
@@ -446,12 +1721,9 @@ mod tests {
         assert_eq!(
             parser.parse_selector(),
             Selector {
-                simple: vec![SimpleSelector {
-                    tag_name: None,
-                    id: Some("we4".to_string()),
-                    classes: vec![]
-                }],
-                combinators: vec![]
+                simple: vec![SimpleSelector::new(None, Some("we4".to_string()), vec![])],
+                combinators: vec![],
+                span: Span::new(0, 4, 1, 1),
             },
             "parse id"
         );
@@ -461,14 +1733,936 @@ mod tests {
         assert_eq!(
             parser.parse_selector(),
             Selector {
-                simple: vec![SimpleSelector {
-                    tag_name: None,
-                    id: Some("first".to_string()),
-                    classes: vec![]
-                }],
-                combinators: vec![]
+                simple: vec![SimpleSelector::new(None, Some("first".to_string()), vec![])],
+                combinators: vec![],
+                span: Span::new(0, 6, 1, 1),
             },
             "parse ids"
         );
     }
+
+    #[test]
+    fn parse_value_with_special_characters() {
+        let (stylesheet, _) = parse(
+            r#"a { content: "a;b}"; background: url(data:image/png;base64,iVBO) } b { font-family: "/*" }"#,
+        );
+
+        assert_eq!(
+            declarations_without_spans(stylesheet.style_rules()[0].declarations.clone()),
+            vec![
+                Declaration::new("content".to_string(), Value::String("a;b}".to_string())),
+                Declaration::new(
+                    "background".to_string(),
+                    Value::Url("data:image/png;base64,iVBO".to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            declarations_without_spans(stylesheet.style_rules()[1].declarations.clone()),
+            vec![Declaration::new(
+                "font-family".to_string(),
+                Value::String("/*".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn parse_declaration_without_semicolon() {
+        assert_eq!(
+            without_spans(parse("body { width: 1px; color: red }").0),
+            without_spans(parse("body { width: 1px; color: red; }").0)
+        );
+    }
+
+    #[test]
+    fn parse_skips_invalid_declarations() {
+        let (stylesheet, diagnostics) = parse("body { width 1px; (a: b); color: red; }");
+
+        assert_eq!(
+            without_spans(stylesheet),
+            without_spans(parse("body { color: red; }").0)
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.severity, d.code, d.message.as_ref()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Severity::Error,
+                    DiagnosticCode::InvalidDeclaration,
+                    "expected `:` after \"width\""
+                ),
+                (
+                    Severity::Error,
+                    DiagnosticCode::InvalidDeclaration,
+                    "expected a property name, found \"(a: b)\""
+                ),
+            ]
+        );
+        assert_eq!(
+            diagnostics[0]
+                .span
+                .source_text("body { width 1px; (a: b); color: red; }"),
+            "width 1px"
+        );
+    }
+
+    #[test]
+    fn parse_reports_diagnostics() {
+        let source = "} a { color: rgba(1, x, 3); width: ; content: \"broken\n; @media print {} }\nb { color: #12345 } c";
+        let (stylesheet, diagnostics) = parse(source);
+
+        assert_eq!(stylesheet.style_rules().len(), 2);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.code, d.span.source_text(source)))
+                .collect::<Vec<_>>(),
+            vec![
+                (DiagnosticCode::BadString, "\"broken"),
+                (DiagnosticCode::UnexpectedToken, "}"),
+                (DiagnosticCode::InvalidColor, "rgba(1, x, 3)"),
+                (DiagnosticCode::EmptyValue, "width:"),
+                (DiagnosticCode::UnsupportedAtRule, "@media print {}"),
+                (DiagnosticCode::InvalidColor, "#12345"),
+                (DiagnosticCode::UnexpectedEof, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_color_keywords() {
+        let (stylesheet, diagnostics) = parse(
+            "a { color: currentColor; background-color: Canvas; border-color: transparent } \
+             b { color: inherit; background-color: ButtonText } c { color: CurrentColour }",
+        );
+
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.code, d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(
+                DiagnosticCode::InvalidColor,
+                "invalid color \"CurrentColour\""
+            )]
+        );
+        assert_eq!(
+            stylesheet.style_rules()[0].declarations[0].value,
+            Value::Color(Color::new("currentColor"))
+        );
+    }
+
+    #[test]
+    fn parse_border_colors() {
+        let (stylesheet, diagnostics) =
+            parse("a { border-color: red blue } b { border-color: #fff 10px }");
+        let declarations = &stylesheet.style_rules()[0].declarations;
+
+        assert_eq!(
+            declarations[0].value,
+            Value::List {
+                separator: Separator::Space,
+                items: vec![
+                    Value::Color(Color::new("red")),
+                    Value::Color(Color::new("blue"))
+                ],
+            }
+        );
+        assert_eq!(
+            declarations[0].value.colors(),
+            vec![&Color::new("red"), &Color::new("blue")]
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.code, d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(DiagnosticCode::InvalidColor, "invalid color \"10px\"")]
+        );
+    }
+
+    #[test]
+    fn parse_at_rules() {
+        let (stylesheet, diagnostics) = parse(
+            r#"@charset "utf-8";
+            @import url("print.css") print;
+            @import 'theme.css';
+            @media screen and (min-width: 900px) {
+                @supports (display: grid) { a { width: 1px; } }
+                b { width: 2px; }
+            }
+            @font-face { font-family: "Inter"; }
+            @-webkit-keyframes fade { from { opacity: 0; } 50%, to { opacity: 1; } }
+            @page :first { margin: 1in; }
+            body { color: red; }"#,
+        );
+
+        assert_eq!(
+            without_spans(stylesheet).rules,
+            vec![
+                CssRule::Other(AtRule {
+                    name: "charset".to_string(),
+                    prelude: "\"utf-8\"".to_string(),
+                    block: None,
+                    span: Span::default(),
+                }),
+                CssRule::Import(ImportRule {
+                    url: "print.css".to_string(),
+                    conditions: "print".to_string(),
+                    span: Span::default(),
+                }),
+                CssRule::Import(ImportRule {
+                    url: "theme.css".to_string(),
+                    conditions: "".to_string(),
+                    span: Span::default(),
+                }),
+                CssRule::Media(ConditionalRule::new(
+                    "screen and (min-width: 900px)".to_string(),
+                    vec![
+                        CssRule::Supports(ConditionalRule::new(
+                            "(display: grid)".to_string(),
+                            without_spans(parse("a { width: 1px; }").0).rules,
+                        )),
+                        without_spans(parse("b { width: 2px; }").0).rules.remove(0),
+                    ]
+                )),
+                CssRule::FontFace(FontFaceRule {
+                    declarations: vec![Declaration::new(
+                        "font-family".to_string(),
+                        Value::String("Inter".to_string())
+                    )],
+                    span: Span::default(),
+                }),
+                CssRule::Keyframes(KeyframesRule {
+                    vendor_prefix: Some("-webkit-".to_string()),
+                    name: "fade".to_string(),
+                    keyframes: vec![
+                        Keyframe {
+                            selectors: vec!["from".to_string()],
+                            declarations: vec![Declaration::new(
+                                "opacity".to_string(),
+                                Value::Integer(0)
+                            )],
+                            span: Span::default(),
+                        },
+                        Keyframe {
+                            selectors: vec!["50%".to_string(), "to".to_string()],
+                            declarations: vec![Declaration::new(
+                                "opacity".to_string(),
+                                Value::Integer(1)
+                            )],
+                            span: Span::default(),
+                        },
+                    ],
+                    span: Span::default(),
+                }),
+                CssRule::Other(AtRule {
+                    name: "page".to_string(),
+                    prelude: ":first".to_string(),
+                    block: Some(" margin: 1in; ".to_string()),
+                    span: Span::default(),
+                }),
+                without_spans(parse("body { color: red; }").0)
+                    .rules
+                    .remove(0),
+            ]
+        );
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn parse_unclosed_at_rule() {
+        assert_eq!(
+            without_spans(parse("@media print { a { color: red; }").0).rules,
+            vec![CssRule::Media(ConditionalRule::new(
+                "print".to_string(),
+                without_spans(parse("a { color: red; }").0).rules
+            ))]
+        );
+    }
+
+    #[test]
+    fn parse_stringify_at_rules_css_file() {
+        let file_path = "../samples/at-rules.css";
+        let contents = read_to_string(file_path).expect("Something went wrong reading the file");
+
+        assert_eq!(contents.trim(), stringify(parse(&contents).0));
+    }
+
+    #[test]
+    fn parse_spans() {
+        let source = "/* header */\r\n#nav, .menu {\r\n    color: red;\r\n    width:1px\r\n}";
+        let (stylesheet, _) = parse(source);
+        let rule = stylesheet.style_rules()[0];
+
+        assert_eq!(
+            (rule.span.line, rule.span.column, rule.span.end),
+            (2, 1, source.len())
+        );
+        assert_eq!(rule.selectors[1].span.source_text(source), ".menu");
+        assert_eq!(
+            (rule.selectors[1].span.line, rule.selectors[1].span.column),
+            (2, 7)
+        );
+
+        let color = &rule.declarations[0];
+        assert_eq!(color.span.source_text(source), "color: red");
+        assert_eq!((color.span.line, color.span.column), (3, 5));
+        assert_eq!(color.value_span.source_text(source), "red");
+        assert_eq!((color.value_span.line, color.value_span.column), (3, 12));
+
+        let width = &rule.declarations[1];
+        assert_eq!(width.span.source_text(source), "width:1px");
+        assert_eq!(
+            width.value_span.snippet(source).unwrap(),
+            "4 |     width:1px\n  |           ^^^"
+        );
+
+        let source = "a {}\n@font-face { font-family: \"Inter\"; }";
+        match &parse(source).0.rules[1] {
+            CssRule::FontFace(rule) => {
+                assert_eq!(rule.span.source_text(source), &source[5..]);
+                assert_eq!((rule.span.line, rule.span.column), (2, 1));
+            }
+            rule => panic!("Parse {:?}", rule),
+        }
+    }
+
+    #[test]
+    fn parse_combinators() {
+        let mut parser = CssParser::new("nav ul > li+a  ~ span, p");
+        let selector = parser.parse_selector();
+
+        assert_eq!(
+            selector.combinators,
+            vec![
+                Combinator::Descendant,
+                Combinator::Child,
+                Combinator::NextSibling,
+                Combinator::SubsequentSibling,
+            ]
+        );
+        assert_eq!(
+            selector
+                .simple
+                .iter()
+                .map(|s| format!("{}", s))
+                .collect::<Vec<String>>(),
+            vec!["nav", "ul", "li", "a", "span"]
+        );
+        assert_eq!(format!("{}", selector), "nav ul > li + a ~ span");
+        assert_eq!(
+            selector.span.source_text("nav ul > li+a  ~ span, p"),
+            "nav ul > li+a  ~ span"
+        );
+    }
+
+    #[test]
+    fn parse_stringify_combinators() {
+        let css = "nav ul > li + a ~ span, .menu #item.active {\r\n    color: red;\r\n}";
+
+        assert_eq!(css, stringify(parse(css).0));
+        assert_eq!(
+            css,
+            stringify(parse("nav  ul>li+a~span,.menu\n#item.active{color:red}").0)
+        );
+    }
+
+    #[test]
+    fn parse_dangling_combinator() {
+        let mut parser = CssParser::new("ul > {");
+        let selector = parser.parse_selector();
+
+        assert_eq!(format!("{}", selector), "ul");
+        assert_eq!(selector.combinators, vec![]);
+    }
+
+    #[test]
+    fn parse_attribute_selectors() {
+        let mut parser = CssParser::new(r#"a[href][ lang |= en ][href$=".pdf" i][data-x="a\"b"]"#);
+        let selector = parser.parse_selector();
+
+        assert_eq!(
+            selector.simple[0].attributes(),
+            vec![
+                &AttributeSelector {
+                    name: "href".to_string(),
+                    matcher: None,
+                },
+                &AttributeSelector {
+                    name: "lang".to_string(),
+                    matcher: Some(AttributeMatcher {
+                        operator: AttributeOperator::DashMatch,
+                        value: "en".to_string(),
+                        case_sensitivity: None,
+                    }),
+                },
+                &AttributeSelector {
+                    name: "href".to_string(),
+                    matcher: Some(AttributeMatcher {
+                        operator: AttributeOperator::Suffix,
+                        value: ".pdf".to_string(),
+                        case_sensitivity: Some(CaseSensitivity::Insensitive),
+                    }),
+                },
+                &AttributeSelector {
+                    name: "data-x".to_string(),
+                    matcher: Some(AttributeMatcher {
+                        operator: AttributeOperator::Equals,
+                        value: "a\"b".to_string(),
+                        case_sensitivity: None,
+                    }),
+                },
+            ]
+        );
+        assert_eq!(
+            format!("{}", selector),
+            r#"a[href][lang|="en"][href$=".pdf" i][data-x="a\"b"]"#
+        );
+    }
+
+    #[test]
+    fn parse_pseudo_classes() {
+        let tests = [
+            ("a:hover", "a:hover"),
+            ("li:NTH-CHILD( 2n + 1 of .x )", "li:nth-child(2n+1 of .x)"),
+            ("li:nth-child(odd)", "li:nth-child(2n+1)"),
+            ("li:nth-last-of-type(-n+3)", "li:nth-last-of-type(-n+3)"),
+            ("li:nth-child(5)", "li:nth-child(5)"),
+            ("p:not(.a,.b)", "p:not(.a, .b)"),
+            ("div:has(> img, p)", "div:has(> img, p)"),
+            ("p:lang(en)", "p:lang(en)"),
+            (":is(h1, h2):where(.x)", ":is(h1, h2):where(.x)"),
+        ];
+
+        for (css, expected) in tests {
+            let mut parser = CssParser::new(css);
+
+            assert_eq!(
+                format!("{}", parser.parse_selector()),
+                expected,
+                "Parse \"{}\"",
+                css
+            );
+            assert_eq!(parser.diagnostics(), [], "Parse \"{}\"", css);
+        }
+
+        let mut parser = CssParser::new("li:nth-child(2n+1 of .x)");
+        assert_eq!(
+            selector_without_spans(parser.parse_selector())
+                .simple
+                .remove(0)
+                .pseudo_classes(),
+            vec![&PseudoClass {
+                name: "nth-child".to_string(),
+                argument: Some(PseudoClassArgument::Nth {
+                    a: 2,
+                    b: 1,
+                    of: vec![Selector {
+                        simple: vec![SimpleSelector::new(None, None, vec!["x".to_string()])],
+                        combinators: vec![],
+                        span: Span::default(),
+                    }],
+                }),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_pseudo_elements() {
+        let tests = [
+            ("p::before", "p::before"),
+            ("p:after", "p::after"),
+            ("a:hover::first-line", "a:hover::first-line"),
+            ("a::before:hover", "a::before:hover"),
+            ("::part(label):focus-visible", "::part(label):focus-visible"),
+            ("::part(label)", "::part(label)"),
+            ("*", "*"),
+            ("*.x", "*.x"),
+        ];
+
+        for (css, expected) in tests {
+            let mut parser = CssParser::new(css);
+
+            assert_eq!(
+                format!("{}", parser.parse_selector()),
+                expected,
+                "Parse \"{}\"",
+                css
+            );
+        }
+
+        let (stylesheet, diagnostics) = parse("a::before:hover { color: red }");
+        assert_eq!(stylesheet.style_rules().len(), 1);
+        assert_eq!(diagnostics, vec![]);
+
+        let mut parser = CssParser::new("*::before");
+        let selector = parser.parse_selector();
+        assert!(selector.simple[0].universal());
+        assert_eq!(
+            selector.simple[0].pseudo_element(),
+            Some(&PseudoElement {
+                name: "before".to_string(),
+                argument: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_invalid_selectors() {
+        let (stylesheet, diagnostics) =
+            parse("li:nth-child(foo), a[href=], p) { color: red; } b { color: blue; }");
+
+        assert_eq!(format!("{}", stylesheet), "b {\r\n    color: blue;\r\n}");
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| format!("{}", d))
+                .collect::<Vec<String>>(),
+            vec![
+                "error[invalid-selector] 1:4: invalid argument \"foo\" of :nth-child()",
+                "error[invalid-selector] 1:21: unsupported selector \"[href=]\"",
+                "error[invalid-selector] 1:31: unexpected `)` in selector",
+            ]
+        );
+
+        let (stylesheet, diagnostics) =
+            parse("a { color: red }\n  { color: blue } b { color: red }");
+        assert_eq!(stylesheet.style_rules().len(), 2);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| format!("{}", d))
+                .collect::<Vec<String>>(),
+            vec!["error[invalid-selector] 2:3: expected a selector before `{`"]
+        );
+
+        // The valid part of a selector list must not apply the declarations
+        // to more elements than the author wrote
+        for css in [
+            "a $ b { color: red }",
+            "a[href=], p { color: red }",
+            "svg|rect { color: red }",
+            "a:nth-child(x) { color: red }",
+            "a::before::after { color: red }",
+            "{ color: red }",
+        ] {
+            let (stylesheet, diagnostics) = parse(css);
+
+            assert_eq!(stylesheet.rules, vec![], "Parse \"{}\"", css);
+            assert_eq!(
+                diagnostics
+                    .iter()
+                    .map(|d| d.code)
+                    .collect::<Vec<DiagnosticCode>>(),
+                vec![DiagnosticCode::InvalidSelector],
+                "Parse \"{}\"",
+                css
+            );
+        }
+    }
+
+    #[test]
+    fn parse_stringify_selectors() {
+        let css = "a[href^=\"https\"]:not(.internal)::after, input[type=\"checkbox\" i]:checked + label, ul > li:nth-child(2n+1 of .item) {\r\n    color: red;\r\n}";
+
+        assert_eq!(css, stringify(parse(css).0));
+
+        // Simple selectors of a compound selector keep their source order
+        for css in [
+            ".a#b",
+            "a:hover.x",
+            "a[href].y:first-child.z",
+            "#a#b",
+            "*.x[y]",
+        ] {
+            let mut parser = CssParser::new(css);
+
+            assert_eq!(format!("{}", parser.parse_selector()), css);
+        }
+    }
+
+    #[test]
+    fn parse_preserves_case() {
+        let css = "DIV#Main.Header-Nav[Data-X=Foo]:HOVER { COLOR: RED; Font-Family: \"Open Sans\", Arial; BACKGROUND: URL(Images/Logo.PNG); --Brand: Blue; WIDTH: 10PX }";
+        let (stylesheet, _) = parse(css);
+        let rule = stylesheet.style_rules()[0];
+
+        assert_eq!(
+            format!("{}", rule.selectors[0]),
+            "div#Main.Header-Nav[data-x=\"Foo\"]:hover"
+        );
+
+        let declarations: Vec<(&str, &str, String, String)> = rule
+            .declarations
+            .iter()
+            .map(|d| {
+                (
+                    d.property.as_str(),
+                    d.raw_property.as_str(),
+                    format!("{}", d.value),
+                    d.normalized_value(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            declarations,
+            vec![
+                ("color", "COLOR", "RED".to_string(), "red".to_string()),
+                (
+                    "font-family",
+                    "Font-Family",
+                    "\"Open Sans\", Arial".to_string(),
+                    "\"Open Sans\", arial".to_string()
+                ),
+                (
+                    "background",
+                    "BACKGROUND",
+                    "url(Images/Logo.PNG)".to_string(),
+                    "url(Images/Logo.PNG)".to_string()
+                ),
+                ("--Brand", "--Brand", "Blue".to_string(), "Blue".to_string()),
+                ("width", "WIDTH", "10px".to_string(), "10px".to_string()),
+            ]
+        );
+        assert_eq!(rule.declarations[0].raw_value, "RED");
+        assert_eq!(rule.declarations[4].raw_value, "10PX");
+
+        let (stylesheet, _) = parse("a { Animation-Name: FadeIn; MARGIN: VAR(--Space, 1PX) 0PX }");
+        let normalized: Vec<String> = stylesheet.style_rules()[0]
+            .declarations
+            .iter()
+            .map(|d| d.normalized_value())
+            .collect();
+        assert_eq!(normalized, vec!["FadeIn", "var(--Space, 1PX) 0px"]);
+    }
+
+    #[test]
+    fn parse_important() {
+        let (stylesheet, diagnostics) = parse(
+            "a { color: red !important; width: 10px ! IMPORTANT; margin: 0 important; content: \"!important\" }",
+        );
+        let declarations = &stylesheet.style_rules()[0].declarations;
+
+        assert_eq!(diagnostics, []);
+        assert_eq!(
+            declarations
+                .iter()
+                .map(|d| (d.important, d.raw_value.as_str()))
+                .collect::<Vec<(bool, &str)>>(),
+            vec![
+                (true, "red"),
+                (true, "10px"),
+                (false, "0 important"),
+                (false, "\"!important\""),
+            ]
+        );
+        assert!(matches!(
+            declarations[0].value,
+            Value::Color(Color {
+                data: ColorData::RGBA { .. },
+                ..
+            })
+        ));
+        assert_eq!(
+            declarations[0]
+                .span
+                .source_text("a { color: red !important;"),
+            "color: red !important"
+        );
+    }
+
+    #[test]
+    fn parse_stringify_important() {
+        let css = "a {\r\n    color: red !important;\r\n    content: \"!important\";\r\n}";
+
+        assert_eq!(css, stringify(parse(css).0));
+        assert_eq!(
+            css,
+            stringify(parse("a{color:red!important;content:\"!important\"}").0)
+        );
+    }
+
+    #[test]
+    fn parse_custom_properties() {
+        let (stylesheet, diagnostics) = parse(
+            ":root { --Brand-Primary: #0AF; --empty:; } \
+             a { color: var(--Brand-Primary, red); border-color: var(--a) var(--b); }",
+        );
+        let rules = stylesheet.style_rules();
+
+        assert_eq!(diagnostics, []);
+        assert_eq!(
+            rules[0].declarations[0].value,
+            Value::Custom {
+                text: "#0AF".to_string(),
+                tokens: vec![Token::Hash("0AF".to_string(), HashType::Unrestricted)],
+            }
+        );
+        assert_eq!(rules[0].declarations[0].property, "--Brand-Primary");
+        assert_eq!(format!("{}", rules[0].declarations[1]), "--empty: ;");
+        assert_eq!(
+            rules[1].declarations[0].value,
+            Value::Var {
+                name: "--Brand-Primary".to_string(),
+                fallback: Some("red".to_string()),
+            }
+        );
+        assert_eq!(
+            rules[1].declarations[1].value,
+            Value::Other("var(--a) var(--b)".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_value_tree() {
+        assert_eq!(
+            parse_value("margin", "0 auto"),
+            Value::List {
+                separator: Separator::Space,
+                items: vec![Value::Integer(0), Value::Keyword("auto".to_string())],
+            }
+        );
+        assert_eq!(
+            parse_value("font-family", "\"Inter\", sans-serif"),
+            Value::List {
+                separator: Separator::Comma,
+                items: vec![
+                    Value::String("Inter".to_string()),
+                    Value::Keyword("sans-serif".to_string())
+                ],
+            }
+        );
+        assert_eq!(
+            parse_value("transform", "ROTATE(45DEG) scale(1.5, -2)"),
+            Value::List {
+                separator: Separator::Space,
+                items: vec![
+                    Value::Function {
+                        name: "rotate".to_string(),
+                        arguments: vec![Value::Dimension(45.0.into(), Unit::Deg)],
+                    },
+                    Value::Function {
+                        name: "scale".to_string(),
+                        arguments: vec![Value::Number(1.5.into()), Value::Integer(-2)],
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            parse_value("grid-area", "1 / 2 span"),
+            Value::List {
+                separator: Separator::Slash,
+                items: vec![
+                    Value::Integer(1),
+                    Value::List {
+                        separator: Separator::Space,
+                        items: vec![Value::Integer(2), Value::Keyword("span".to_string())],
+                    },
+                ],
+            }
+        );
+        assert_eq!(parse_value("width", "50%"), Value::Percentage(50.0.into()));
+        assert_eq!(
+            parse_value("width", "10PX"),
+            Value::Length(10.0.into(), Unit::Px)
+        );
+        assert_eq!(
+            parse_value("background", "url(a.png) no-repeat, #FFF"),
+            Value::List {
+                separator: Separator::Comma,
+                items: vec![
+                    Value::List {
+                        separator: Separator::Space,
+                        items: vec![
+                            Value::Url("a.png".to_string()),
+                            Value::Keyword("no-repeat".to_string())
+                        ],
+                    },
+                    Value::Color(Color::new("#FFF")),
+                ],
+            }
+        );
+        assert_eq!(
+            parse_value("grid-template-columns", "[full] 1fr"),
+            Value::List {
+                separator: Separator::Space,
+                items: vec![
+                    Value::Other("[full]".to_string()),
+                    Value::Dimension(1.0.into(), Unit::Fr)
+                ],
+            }
+        );
+        assert_eq!(
+            parse_value("filter", "none()"),
+            Value::Function {
+                name: "none".to_string(),
+                arguments: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_stringify_value_tree() {
+        let tests = [
+            (
+                "box-shadow",
+                "0 1px 2px rgba(0, 0, 0, 0.5), inset 0 0 0 1px #fff",
+            ),
+            ("font", "12px / 1.5 \"Helvetica Neue\", sans-serif"),
+            ("transform", "translate(-50%, -50%) rotate(45deg)"),
+            ("background", "url(a.png) center / cover no-repeat"),
+            ("content", "\"a\\\"b\""),
+        ];
+
+        for (property, value) in tests {
+            assert_eq!(
+                format!("{}", parse_value(property, value)),
+                value,
+                "Parse \"{}\"",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn parse_calc() {
+        let value = parse_value("width", "calc(100% - 2 * var(--gutter))");
+
+        assert_eq!(
+            value,
+            Value::Calc(Calc::Function {
+                function: MathFunction::Calc,
+                arguments: vec![Calc::operation(
+                    Calc::value(Value::Percentage(100.0.into())),
+                    Operator::Subtract,
+                    Calc::operation(
+                        Calc::value(Value::Integer(2)),
+                        Operator::Multiply,
+                        Calc::value(Value::Var {
+                            name: "--gutter".to_string(),
+                            fallback: None,
+                        }),
+                    ),
+                )],
+            })
+        );
+
+        let tests = [
+            "calc(100% - 2 * var(--gutter))",
+            "clamp(1rem, 2.5vw, 2rem)",
+            "min(10px, 5vw) max(1em, 2vh)",
+            "calc((1px + 2px) * 3)",
+            "calc(1px - (2px - 3px))",
+            "calc(2 * pi * 1rad)",
+            "calc(1px + min(2px, 3vw) / 2)",
+            "translatex(calc(-1 * var(--offset, 10px)))",
+        ];
+
+        for value in tests {
+            assert_eq!(
+                format!("{}", parse_value("width", value)),
+                value,
+                "Parse \"{}\"",
+                value
+            );
+        }
+
+        // Not a math expression, parsed as a plain function
+        for value in ["calc(1px +2px)", "calc(1px, 2px)", "clamp(1px, 2px)"] {
+            assert!(
+                matches!(parse_value("width", value), Value::Function { .. }),
+                "Parse \"{}\"",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn parse_invalid_calc() {
+        let (stylesheet, diagnostics) =
+            parse("a { width: calc(1px + 1deg); height: min(1px, 2s); top: calc(1px * 2px); }");
+
+        assert_eq!(format!("{}", stylesheet), "a {\r\n}");
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| format!("{}", d))
+                .collect::<Vec<String>>(),
+            vec![
+                "error[invalid-value] 1:12: cannot add length and angle",
+                "error[invalid-value] 1:38: cannot add length and time",
+                "error[invalid-value] 1:57: cannot multiply length by length",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_modern_colors() {
+        let (stylesheet, diagnostics) = parse(
+            ":root { color: oklch(0.7 0.15 180); background-color: color(display-p3 1 0 0); border-color: hwb(0 0% 0%); outline-color: lab(50 20 30) }",
+        );
+
+        assert_eq!(diagnostics, vec![]);
+        for declaration in &stylesheet.style_rules()[0].declarations {
+            assert!(
+                matches!(declaration.value, Value::Color(_)),
+                "Parse \"{}\"",
+                declaration
+            );
+        }
+    }
+
+    #[test]
+    fn parse_units() {
+        let tests = [
+            ("100dvh", Value::Length(100.0.into(), Unit::Dvh)),
+            ("50cqi", Value::Length(50.0.into(), Unit::Cqi)),
+            ("1FR", Value::Dimension(1.0.into(), Unit::Fr)),
+            ("0.5s", Value::Dimension(0.5.into(), Unit::S)),
+            ("2dppx", Value::Dimension(2.0.into(), Unit::Dppx)),
+            (
+                "3quux",
+                Value::Dimension(3.0.into(), Unit::Unknown("quux".to_string())),
+            ),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(parse_value("width", value), expected, "Parse \"{}\"", value);
+        }
+    }
+
+    #[test]
+    fn parse_numbers() {
+        let tests = [
+            ("-10px", Value::Length((-10.0).into(), Unit::Px)),
+            ("+.5em", Value::Length(0.5.into(), Unit::Em)),
+            ("1e3px", Value::Length(1000.0.into(), Unit::Px)),
+            ("1.5E-1px", Value::Length(0.15.into(), Unit::Px)),
+            ("-2.5e+2%", Value::Percentage((-250.0).into())),
+            ("-.75", Value::Number((-0.75).into())),
+            ("-3", Value::Integer(-3)),
+            (
+                "2e",
+                Value::Dimension(2.0.into(), Unit::Unknown("e".to_string())),
+            ),
+        ];
+
+        for (value, expected) in tests {
+            let parsed = parse_value("margin-top", value);
+
+            assert_eq!(parsed, expected, "Parse \"{}\"", value);
+            assert_eq!(format!("{}", parsed), value, "Parse \"{}\"", value);
+        }
+    }
+
+    #[test]
+    fn parse_stringify_numbers() {
+        let css =
+            "a {\r\n    margin: -10px .5em 1e3px +1.50%;\r\n    transition: opacity .5s;\r\n}";
+
+        assert_eq!(css, stringify(parse(css).0));
+    }
 }
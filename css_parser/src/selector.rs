@@ -1,21 +1,110 @@
-#[derive(PartialEq, Eq)]
+use crate::span::Span;
+
+/// Complex selector, e.g. `nav ul > li`.
+///
+/// `combinators[i]` joins `simple[i]` and `simple[i + 1]`.
+#[derive(PartialEq, Eq, Default)]
 pub struct Selector {
     pub simple: Vec<SimpleSelector>,
-    pub combinators: Vec<char>,
+    pub combinators: Vec<Combinator>,
+    pub span: Span,
+}
+/// Selector specificity as (ids, classes, types).
+///
+/// Tuples compare lexicographically, so a higher specificity compares greater.
+pub type Specificity = (u32, u32, u32);
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Combinator {
+    /// Example: nav a
+    Descendant,
+    /// Example: ul > li
+    Child,
+    /// Example: h1 + p
+    NextSibling,
+    /// Example: h1 ~ p
+    SubsequentSibling,
 }
-#[derive(PartialEq, Eq)]
+/// Compound selector, e.g. `a#page.wrapper[href]:hover::before`
+#[derive(PartialEq, Eq, Default)]
 pub struct SimpleSelector {
-    /// # Example: *
-    // pub any: Option<bool>,
+    /// Simple selectors in source order
+    pub components: Vec<SelectorComponent>,
+}
+#[derive(PartialEq, Eq, Debug)]
+pub enum SelectorComponent {
+    /// Example: *
+    Universal,
     /// Example: div
-    pub tag_name: Option<String>,
-    /// # Example: [name="value"]
-    // pub attribute: Option<String>,
-    /// # Example: :visited
-    // pub pseudo_class: Option<String>,
-
+    Type(String),
     /// Example: #page
-    pub id: Option<String>,
+    Id(String),
     /// Example: .wrapper
-    pub classes: Vec<String>,
+    Class(String),
+    /// Example: [name="value"]
+    Attribute(AttributeSelector),
+    /// Example: :visited
+    PseudoClass(PseudoClass),
+    /// Example: ::before
+    PseudoElement(PseudoElement),
+}
+#[derive(PartialEq, Eq, Debug)]
+pub struct AttributeSelector {
+    pub name: String,
+    /// `None` for [name], which only checks that the attribute is present
+    pub matcher: Option<AttributeMatcher>,
+}
+#[derive(PartialEq, Eq, Debug)]
+pub struct AttributeMatcher {
+    pub operator: AttributeOperator,
+    pub value: String,
+    /// Example: i in [type="a" i]
+    pub case_sensitivity: Option<CaseSensitivity>,
+}
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AttributeOperator {
+    /// Example: [lang="en"]
+    Equals,
+    /// Example: [class~="logo"]
+    Includes,
+    /// Example: [lang|="en"]
+    DashMatch,
+    /// Example: [href^="https"]
+    Prefix,
+    /// Example: [href$=".pdf"]
+    Suffix,
+    /// Example: [href*="example"]
+    Substring,
+}
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CaseSensitivity {
+    /// Example: [type="a" i]
+    Insensitive,
+    /// Example: [type="a" s]
+    Sensitive,
+}
+#[derive(PartialEq, Eq, Debug)]
+pub struct PseudoClass {
+    /// Example: hover, nth-child
+    pub name: String,
+    /// `None` for non-functional pseudo-classes
+    pub argument: Option<PseudoClassArgument>,
+}
+#[derive(PartialEq, Eq, Debug)]
+pub enum PseudoClassArgument {
+    /// Example: :not(.a, .b), :is(), :where()
+    Selectors(Vec<Selector>),
+    /// Example: :has(> img, p), the combinator is `Descendant` when omitted
+    Relative(Vec<(Combinator, Selector)>),
+    /// Example: :nth-child(2n+1 of .x), `of` is empty when omitted
+    Nth { a: i32, b: i32, of: Vec<Selector> },
+    /// Any other argument kept as text, e.g. :lang(en)
+    Other(String),
+}
+#[derive(PartialEq, Eq, Debug)]
+pub struct PseudoElement {
+    /// Example: before, part
+    pub name: String,
+    /// Example: `label` in ::part(label)
+    pub argument: Option<String>,
 }
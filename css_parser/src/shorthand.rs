@@ -0,0 +1,308 @@
+use crate::structs::{Declaration, Separator, Value};
+
+/// Shorthands made of up to four box sides, or of a row and a column gap
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Shorthand {
+    /// Example: margin: 0 auto
+    Margin,
+    /// Example: padding: 4px 8px
+    Padding,
+    /// Example: inset: 0
+    Inset,
+    /// Example: border-width: 1px 0
+    BorderWidth,
+    /// Example: gap: 1rem 2rem
+    Gap,
+}
+
+impl Shorthand {
+    /// Shorthand of a lowercased property name
+    pub fn new(property: &str) -> Option<Shorthand> {
+        match property {
+            "margin" => Some(Shorthand::Margin),
+            "padding" => Some(Shorthand::Padding),
+            "inset" => Some(Shorthand::Inset),
+            "border-width" => Some(Shorthand::BorderWidth),
+            "gap" => Some(Shorthand::Gap),
+            _ => None,
+        }
+    }
+
+    pub fn property(&self) -> &'static str {
+        match self {
+            Shorthand::Margin => "margin",
+            Shorthand::Padding => "padding",
+            Shorthand::Inset => "inset",
+            Shorthand::BorderWidth => "border-width",
+            Shorthand::Gap => "gap",
+        }
+    }
+
+    /// Longhands in the order of the shorthand values: top, right, bottom, left
+    /// for box sides, row then column for gaps
+    pub fn longhands(&self) -> &'static [&'static str] {
+        match self {
+            Shorthand::Margin => &["margin-top", "margin-right", "margin-bottom", "margin-left"],
+            Shorthand::Padding => &[
+                "padding-top",
+                "padding-right",
+                "padding-bottom",
+                "padding-left",
+            ],
+            Shorthand::Inset => &["top", "right", "bottom", "left"],
+            Shorthand::BorderWidth => &[
+                "border-top-width",
+                "border-right-width",
+                "border-bottom-width",
+                "border-left-width",
+            ],
+            Shorthand::Gap => &["row-gap", "column-gap"],
+        }
+    }
+}
+
+/// Longhands of a shorthand declaration, e.g. `margin: 0 auto` gives
+/// `margin-top: 0`, `margin-right: auto`, `margin-bottom: 0` and `margin-left: auto`.
+///
+/// Returns `None` if the property is not a supported shorthand or the value
+/// can't be split, e.g. it has the wrong number of values or uses `var()`.
+pub fn expand(declaration: &Declaration) -> Option<Vec<Declaration>> {
+    let shorthand = Shorthand::new(&declaration.property)?;
+    let values = match declaration.value {
+        Value::List {
+            separator: Separator::Space,
+            ref items,
+        } => items.iter().collect(),
+        Value::List { .. } | Value::Var { .. } | Value::Other(_) => return None,
+        ref value => vec![value],
+    };
+    // A var() may substitute any number of values
+    if values
+        .iter()
+        .any(|value| matches!(value, Value::Var { .. }))
+    {
+        return None;
+    }
+
+    let sides = match (shorthand, values.as_slice()) {
+        (Shorthand::Gap, [row]) => vec![*row, *row],
+        (Shorthand::Gap, [row, column]) => vec![*row, *column],
+        (Shorthand::Gap, _) => return None,
+        (_, [all]) => vec![*all, *all, *all, *all],
+        (_, [vertical, horizontal]) => vec![*vertical, *horizontal, *vertical, *horizontal],
+        (_, [top, horizontal, bottom]) => vec![*top, *horizontal, *bottom, *horizontal],
+        (_, [top, right, bottom, left]) => vec![*top, *right, *bottom, *left],
+        _ => return None,
+    };
+
+    let longhands = shorthand
+        .longhands()
+        .iter()
+        .zip(sides)
+        .map(|(property, value)| {
+            let mut longhand = Declaration::new(property.to_string(), value.clone());
+            longhand.important = declaration.important;
+            longhand.span = declaration.span;
+            longhand.value_span = declaration.value_span;
+            longhand
+        })
+        .collect();
+
+    Some(longhands)
+}
+
+/// Shortest shorthand declaration equal to the longhands in `declarations`.
+///
+/// The last declaration of each longhand wins, as in the cascade. Returns `None`
+/// if a longhand is missing or they don't have the same `!important` flag.
+pub fn collapse(shorthand: Shorthand, declarations: &[Declaration]) -> Option<Declaration> {
+    let mut sides = Vec::new();
+
+    for longhand in shorthand.longhands() {
+        let declaration = declarations
+            .iter()
+            .rev()
+            .find(|declaration| declaration.property == *longhand)?;
+
+        if let Value::List { .. } | Value::Var { .. } | Value::Other(_) = declaration.value {
+            return None;
+        }
+        sides.push(declaration);
+    }
+    if sides
+        .iter()
+        .any(|side| side.important != sides[0].important)
+    {
+        return None;
+    }
+
+    let values: Vec<&Value> = sides.iter().map(|side| &side.value).collect();
+    let values = match values.as_slice() {
+        [row, column] if row == column => vec![*row],
+        [row, column] => vec![*row, *column],
+        [top, right, bottom, left] if top == bottom && right == left && top == right => {
+            vec![*top]
+        }
+        [top, right, bottom, left] if top == bottom && right == left => vec![*top, *right],
+        [top, right, bottom, left] if right == left => vec![*top, *right, *bottom],
+        _ => values,
+    };
+
+    let value = match values.len() {
+        1 => values[0].clone(),
+        _ => Value::List {
+            separator: Separator::Space,
+            items: values.into_iter().cloned().collect(),
+        },
+    };
+
+    let mut declaration = Declaration::new(shorthand.property().to_string(), value);
+    declaration.important = sides[0].important;
+    declaration.span = sides[sides.len() - 1].span;
+
+    Some(declaration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parser::parse;
+    use crate::span::Span;
+
+    fn declarations(css: &str) -> Vec<Declaration> {
+        let (stylesheet, _) = parse(&format!("a {{ {} }}", css));
+        let rule = stylesheet.style_rules().remove(0);
+
+        rule.declarations.clone()
+    }
+
+    fn expanded(css: &str) -> Vec<String> {
+        expand(&declarations(css)[0])
+            .unwrap()
+            .iter()
+            .map(|declaration| format!("{}", declaration))
+            .collect()
+    }
+
+    #[test]
+    fn expand_shorthands() {
+        assert_eq!(
+            expanded("margin: 0 auto 1rem"),
+            vec![
+                "margin-top: 0;",
+                "margin-right: auto;",
+                "margin-bottom: 1rem;",
+                "margin-left: auto;"
+            ]
+        );
+        assert_eq!(
+            expanded("padding: 4px 8px !important"),
+            vec![
+                "padding-top: 4px !important;",
+                "padding-right: 8px !important;",
+                "padding-bottom: 4px !important;",
+                "padding-left: 8px !important;"
+            ]
+        );
+        assert_eq!(
+            expanded("inset: 0"),
+            vec!["top: 0;", "right: 0;", "bottom: 0;", "left: 0;"]
+        );
+        assert_eq!(
+            expanded("border-width: 1px 2px 3px 4px"),
+            vec![
+                "border-top-width: 1px;",
+                "border-right-width: 2px;",
+                "border-bottom-width: 3px;",
+                "border-left-width: 4px;"
+            ]
+        );
+        assert_eq!(
+            expanded("gap: 1rem calc(2rem + 1px)"),
+            vec!["row-gap: 1rem;", "column-gap: calc(2rem + 1px);"]
+        );
+
+        for css in [
+            "margin: 1px 2px 3px 4px 5px",
+            "margin: var(--space)",
+            "margin: var(--space) 0",
+            "gap: 1px 2px 3px",
+            "color: red",
+        ] {
+            assert_eq!(expand(&declarations(css)[0]), None, "expand {}", css);
+        }
+    }
+
+    #[test]
+    fn collapse_longhands() {
+        let tests = [
+            (
+                Shorthand::Margin,
+                "margin-top: 1px; margin-right: 2px; margin-bottom: 3px; margin-left: 4px",
+                Some("margin: 1px 2px 3px 4px;"),
+            ),
+            (
+                Shorthand::Margin,
+                "margin-top: 0; margin-right: auto; margin-bottom: 1rem; margin-left: auto",
+                Some("margin: 0 auto 1rem;"),
+            ),
+            (
+                Shorthand::Padding,
+                "padding-top: 4px; padding-right: 8px; padding-bottom: 4px; padding-left: 8px",
+                Some("padding: 4px 8px;"),
+            ),
+            (
+                Shorthand::Padding,
+                "padding-top: 1px; padding-top: 0; padding-right: 0; padding-bottom: 0; padding-left: 0",
+                Some("padding: 0;"),
+            ),
+            (
+                Shorthand::Gap,
+                "row-gap: 1rem; column-gap: 1rem",
+                Some("gap: 1rem;"),
+            ),
+            (
+                Shorthand::Margin,
+                "margin-top: 1px; margin-right: 2px; margin-bottom: 3px",
+                None,
+            ),
+            (
+                Shorthand::Margin,
+                "margin-top: 0 !important; margin-right: 0; margin-bottom: 0; margin-left: 0",
+                None,
+            ),
+        ];
+
+        for (shorthand, css, expected) in tests {
+            assert_eq!(
+                collapse(shorthand, &declarations(css)).map(|d| format!("{}", d)),
+                expected.map(String::from),
+                "collapse {}",
+                css
+            );
+        }
+    }
+
+    #[test]
+    fn expand_collapse() {
+        for css in [
+            "margin: 0 auto 1rem",
+            "padding: 1px 2px 3px 4px",
+            "gap: 1px 2px",
+        ] {
+            let declaration = &declarations(css)[0];
+            let shorthand = Shorthand::new(&declaration.property).unwrap();
+            // A collapsed value is made of several longhand values, it has no span
+            let mut expected = declaration.clone();
+            expected.value_span = Span::default();
+
+            assert_eq!(
+                collapse(shorthand, &expand(declaration).unwrap()),
+                Some(expected),
+                "collapse expanded {}",
+                css
+            );
+        }
+    }
+}
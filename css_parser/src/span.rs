@@ -0,0 +1,145 @@
+use std::fmt;
+
+/// Location of a node in the source text.
+///
+/// `start` and `end` are byte offsets, `line` and `column` are 1-based and
+/// point to the first code point of the node.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Span {
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// Source text covered by the span
+    pub fn source_text<'a>(&self, source: &'a str) -> &'a str {
+        source.get(self.start..self.end).unwrap_or("")
+    }
+
+    /// First line of the span with the covered part underlined, e.g.
+    ///
+    /// ```text
+    /// 3 |     colr: red;
+    ///   |     ^^^^^^^^^
+    /// ```
+    ///
+    /// Returns `None` if the span doesn't point into `source`, e.g. the default
+    /// span of a node built in code.
+    pub fn snippet(&self, source: &str) -> Option<String> {
+        if self.line == 0 {
+            return None;
+        }
+        let line_start = source
+            .get(..self.start)?
+            .rfind(['\n', '\r', '\u{000C}'])
+            .map_or(0, |i| i + 1);
+        let line_text = source[line_start..]
+            .split(['\n', '\r', '\u{000C}'])
+            .next()
+            .unwrap_or("");
+
+        let underlined = source
+            .get(self.start..self.end)?
+            .lines()
+            .next()
+            .unwrap_or("");
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+
+        Some(format!(
+            "{} | {}\n{} | {}{}",
+            number,
+            line_text,
+            gutter,
+            " ".repeat(self.column.saturating_sub(1)),
+            "^".repeat(underlined.chars().count().max(1))
+        ))
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Maps byte offsets of a source text to line and column numbers.
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> LineIndex {
+        let mut line_starts = vec![0];
+        let mut chars = source.char_indices().peekable();
+
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '\r' if chars.peek().map(|(_, c)| *c) == Some('\n') => {}
+                '\n' | '\r' | '\u{000C}' => line_starts.push(offset + 1),
+                _ => {}
+            }
+        }
+
+        LineIndex { line_starts }
+    }
+
+    pub fn span(&self, source: &str, start: usize, end: usize) -> Span {
+        let line = match self.line_starts.binary_search(&start) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        };
+        let column = source[self.line_starts[line]..start].chars().count() + 1;
+
+        Span::new(start, end, line + 1, column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_and_column() {
+        let source = "a {\r\n  b: c;\n}\u{000C}é d";
+        let lines = LineIndex::new(source);
+
+        let span = lines.span(source, 7, 11);
+        assert_eq!((span.line, span.column), (2, 3));
+        assert_eq!(span.source_text(source), "b: c");
+
+        let span = lines.span(source, 18, 19);
+        assert_eq!((span.line, span.column), (4, 3));
+        assert_eq!(span.source_text(source), "d");
+
+        let span = lines.span(source, 0, 1);
+        assert_eq!((span.line, span.column), (1, 1));
+    }
+
+    #[test]
+    fn snippet() {
+        let source = "body {\n    colr: red;\n}";
+        let span = LineIndex::new(source).span(source, 11, 20);
+
+        assert_eq!(
+            span.snippet(source).unwrap(),
+            "2 |     colr: red;\n  |     ^^^^^^^^^"
+        );
+        assert_eq!(format!("{}", span), "2:5");
+
+        // Nodes built in code, e.g. by `Declaration::new`, have a default span
+        assert_eq!(Span::default().snippet(source), None);
+        assert_eq!(Span::new(11, 40, 2, 5).snippet(source), None);
+    }
+}
@@ -1,91 +1,521 @@
+use super::calc::Calc;
 use super::color::Color;
-use super::selector::{Selector, SimpleSelector};
+use super::length::LengthContext;
+use super::selector::{
+    AttributeMatcher, AttributeOperator, AttributeSelector, CaseSensitivity, Combinator,
+    PseudoClass, PseudoClassArgument, PseudoElement, Selector, SelectorComponent, SimpleSelector,
+    Specificity,
+};
+use super::span::Span;
+use super::tokenizer::normalize_case;
+use super::tokenizer::Token;
 
-use std::default::Default;
 use std::fmt;
 use std::fmt::Formatter;
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Default)]
 pub struct Stylesheet {
-    pub rules: Vec<Rule>,
+    pub rules: Vec<CssRule>,
 }
-#[derive(PartialEq)]
+#[derive(PartialEq, Debug)]
+pub enum CssRule {
+    /// Example: body { color: red; }
+    Style(Rule),
+    /// Example: @media screen and (min-width: 900px) { ... }
+    Media(ConditionalRule),
+    /// Example: @supports (display: grid) { ... }
+    Supports(ConditionalRule),
+    /// Example: @import url("print.css") print;
+    Import(ImportRule),
+    /// Example: @font-face { font-family: "Inter"; }
+    FontFace(FontFaceRule),
+    /// Example: @keyframes fade { from { opacity: 0; } }
+    Keyframes(KeyframesRule),
+    /// Any other at-rule, e.g. @charset or @page, kept as source text
+    Other(AtRule),
+}
+/// At-rule with a condition and a nested list of rules
+#[derive(PartialEq, Debug)]
+pub struct ConditionalRule {
+    pub condition: String,
+    pub rules: Vec<CssRule>,
+    pub span: Span,
+}
+#[derive(PartialEq, Debug)]
+pub struct ImportRule {
+    pub url: String,
+    /// Media queries, `supports()` and `layer` after the url
+    pub conditions: String,
+    pub span: Span,
+}
+#[derive(PartialEq, Debug)]
+pub struct FontFaceRule {
+    pub declarations: Vec<Declaration>,
+    pub span: Span,
+}
+#[derive(PartialEq, Debug)]
+pub struct KeyframesRule {
+    /// Example: `-webkit-` for @-webkit-keyframes
+    pub vendor_prefix: Option<String>,
+    pub name: String,
+    pub keyframes: Vec<Keyframe>,
+    pub span: Span,
+}
+#[derive(PartialEq, Debug)]
+pub struct Keyframe {
+    /// Example: from, 50%
+    pub selectors: Vec<String>,
+    pub declarations: Vec<Declaration>,
+    pub span: Span,
+}
+#[derive(PartialEq, Debug)]
+pub struct AtRule {
+    pub name: String,
+    pub prelude: String,
+    /// Content between the braces, `None` for statements ending with `;`
+    pub block: Option<String>,
+    pub span: Span,
+}
+#[derive(PartialEq, Default)]
 pub struct Rule {
     pub selectors: Vec<Selector>,
     pub declarations: Vec<Declaration>,
+    pub span: Span,
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub struct Declaration {
+    /// Lowercased property name, custom properties keep their case
     pub property: String,
+    /// Property name as written in the source
+    pub raw_property: String,
     pub value: Value,
+    /// Value as written in the source, without surrounding whitespace and `!important`
+    pub raw_value: String,
+    pub important: bool,
+    pub span: Span,
+    pub value_span: Span,
 }
-#[derive(PartialEq)]
+/// Component value tree of a declaration value
+#[derive(PartialEq, Clone)]
 pub enum Value {
+    /// Identifier as written, e.g. auto, sans-serif
+    Keyword(String),
+    /// Example: 1.5
+    Number(Number),
+    /// Example: -1
+    Integer(i32),
+    /// Example: 50%
+    Percentage(Number),
+    /// Dimension with a length unit, e.g. 10px
+    Length(Number, Unit),
+    /// Dimension with any other unit, e.g. 45deg, 1fr
+    Dimension(Number, Unit),
+    /// Content of a quoted string, e.g. "Inter"
+    String(String),
+    /// Content of an unquoted url(), e.g. url(image.png)
+    Url(String),
     Color(Color),
-    Length(f32, Unit),
+    /// Example: rotate(45deg), `arguments` are separated by commas
+    Function {
+        name: String,
+        arguments: Vec<Value>,
+    },
+    /// Math function, e.g. calc(100% - 2rem), clamp(1rem, 2.5vw, 2rem)
+    Calc(Calc),
+    /// Example: 0 auto, "Inter", sans-serif
+    List {
+        separator: Separator,
+        items: Vec<Value>,
+    },
+    /// Value of a custom property, e.g. `--brand: #0af`. It is kept as written,
+    /// `tokens` are only meaningful once the value is substituted into a `var()`.
+    Custom {
+        text: String,
+        tokens: Vec<Token>,
+    },
+    /// Example: var(--brand, red)
+    Var {
+        name: String,
+        fallback: Option<String>,
+    },
+    /// Source text of anything else, e.g. a stray delimiter or a block
     Other(String),
 }
-#[derive(PartialEq)]
+/// Numeric part of a value.
+///
+/// `repr` is the number as written, e.g. `.5` or `1e3`, so values serialize
+/// back the way they were written. It is empty for computed numbers and is
+/// ignored when numbers are compared.
+#[derive(Clone, Default, Debug)]
+pub struct Number {
+    pub value: f32,
+    pub repr: String,
+}
+/// Separator of a `Value::List`.
+///
+/// Commas separate the loosest, then slashes, then whitespace, so
+/// `a b / c, d` is `[[[a b] / c], d]`.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Separator {
+    Space,
+    Slash,
+    Comma,
+}
+/// Unit of a dimension, from CSS Values and Units Level 4
+#[derive(PartialEq, Clone, Debug)]
 pub enum Unit {
+    // Font-relative lengths
     Em,
+    Rem,
     Ex,
+    Rex,
+    Cap,
+    Rcap,
     Ch,
-    Rem,
-    Vh,
+    Rch,
+    Ic,
+    Ric,
+    Lh,
+    Rlh,
+    // Viewport-percentage lengths
     Vw,
+    Vh,
+    Vi,
+    Vb,
     Vmin,
     Vmax,
+    Svw,
+    Svh,
+    Svi,
+    Svb,
+    Svmin,
+    Svmax,
+    Lvw,
+    Lvh,
+    Lvi,
+    Lvb,
+    Lvmin,
+    Lvmax,
+    Dvw,
+    Dvh,
+    Dvi,
+    Dvb,
+    Dvmin,
+    Dvmax,
+    // Container query lengths
+    Cqw,
+    Cqh,
+    Cqi,
+    Cqb,
+    Cqmin,
+    Cqmax,
+    // Absolute lengths
     Px,
+    Cm,
     Mm,
     Q,
-    Cm,
     In,
     Pt,
     Pc,
+    // Angles
+    Deg,
+    Grad,
+    Rad,
+    Turn,
+    // Durations
+    S,
+    Ms,
+    // Frequencies
+    Hz,
+    KHz,
+    // Resolutions
+    Dpi,
+    Dpcm,
+    Dppx,
+    X,
+    // Flexible lengths
+    Fr,
+    /// Percent sign, percentages in values are parsed as `Value::Percentage`
     Pct,
+    /// Any other unit, lowercased
+    Unknown(String),
+}
+/// Type of quantity a unit measures
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum UnitKind {
+    Length,
+    Angle,
+    Time,
+    Frequency,
+    Resolution,
+    Flex,
+    Percentage,
+    Unknown,
 }
 
-#[allow(unreachable_patterns)]
-impl fmt::Display for Unit {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+impl Unit {
+    /// Unit of a lowercased unit name, e.g. `px`
+    pub fn new(unit: &str) -> Unit {
+        match unit {
+            "em" => Unit::Em,
+            "rem" => Unit::Rem,
+            "ex" => Unit::Ex,
+            "rex" => Unit::Rex,
+            "cap" => Unit::Cap,
+            "rcap" => Unit::Rcap,
+            "ch" => Unit::Ch,
+            "rch" => Unit::Rch,
+            "ic" => Unit::Ic,
+            "ric" => Unit::Ric,
+            "lh" => Unit::Lh,
+            "rlh" => Unit::Rlh,
+            "vw" => Unit::Vw,
+            "vh" => Unit::Vh,
+            "vi" => Unit::Vi,
+            "vb" => Unit::Vb,
+            "vmin" => Unit::Vmin,
+            "vmax" => Unit::Vmax,
+            "svw" => Unit::Svw,
+            "svh" => Unit::Svh,
+            "svi" => Unit::Svi,
+            "svb" => Unit::Svb,
+            "svmin" => Unit::Svmin,
+            "svmax" => Unit::Svmax,
+            "lvw" => Unit::Lvw,
+            "lvh" => Unit::Lvh,
+            "lvi" => Unit::Lvi,
+            "lvb" => Unit::Lvb,
+            "lvmin" => Unit::Lvmin,
+            "lvmax" => Unit::Lvmax,
+            "dvw" => Unit::Dvw,
+            "dvh" => Unit::Dvh,
+            "dvi" => Unit::Dvi,
+            "dvb" => Unit::Dvb,
+            "dvmin" => Unit::Dvmin,
+            "dvmax" => Unit::Dvmax,
+            "cqw" => Unit::Cqw,
+            "cqh" => Unit::Cqh,
+            "cqi" => Unit::Cqi,
+            "cqb" => Unit::Cqb,
+            "cqmin" => Unit::Cqmin,
+            "cqmax" => Unit::Cqmax,
+            "px" => Unit::Px,
+            "cm" => Unit::Cm,
+            "mm" => Unit::Mm,
+            "q" => Unit::Q,
+            "in" => Unit::In,
+            "pt" => Unit::Pt,
+            "pc" => Unit::Pc,
+            "deg" => Unit::Deg,
+            "grad" => Unit::Grad,
+            "rad" => Unit::Rad,
+            "turn" => Unit::Turn,
+            "s" => Unit::S,
+            "ms" => Unit::Ms,
+            "hz" => Unit::Hz,
+            "khz" => Unit::KHz,
+            "dpi" => Unit::Dpi,
+            "dpcm" => Unit::Dpcm,
+            "dppx" => Unit::Dppx,
+            "x" => Unit::X,
+            "fr" => Unit::Fr,
+            "%" => Unit::Pct,
+            _ => Unit::Unknown(unit.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Unit::Em => "em",
+            Unit::Rem => "rem",
+            Unit::Ex => "ex",
+            Unit::Rex => "rex",
+            Unit::Cap => "cap",
+            Unit::Rcap => "rcap",
+            Unit::Ch => "ch",
+            Unit::Rch => "rch",
+            Unit::Ic => "ic",
+            Unit::Ric => "ric",
+            Unit::Lh => "lh",
+            Unit::Rlh => "rlh",
+            Unit::Vw => "vw",
+            Unit::Vh => "vh",
+            Unit::Vi => "vi",
+            Unit::Vb => "vb",
+            Unit::Vmin => "vmin",
+            Unit::Vmax => "vmax",
+            Unit::Svw => "svw",
+            Unit::Svh => "svh",
+            Unit::Svi => "svi",
+            Unit::Svb => "svb",
+            Unit::Svmin => "svmin",
+            Unit::Svmax => "svmax",
+            Unit::Lvw => "lvw",
+            Unit::Lvh => "lvh",
+            Unit::Lvi => "lvi",
+            Unit::Lvb => "lvb",
+            Unit::Lvmin => "lvmin",
+            Unit::Lvmax => "lvmax",
+            Unit::Dvw => "dvw",
+            Unit::Dvh => "dvh",
+            Unit::Dvi => "dvi",
+            Unit::Dvb => "dvb",
+            Unit::Dvmin => "dvmin",
+            Unit::Dvmax => "dvmax",
+            Unit::Cqw => "cqw",
+            Unit::Cqh => "cqh",
+            Unit::Cqi => "cqi",
+            Unit::Cqb => "cqb",
+            Unit::Cqmin => "cqmin",
+            Unit::Cqmax => "cqmax",
+            Unit::Px => "px",
+            Unit::Cm => "cm",
+            Unit::Mm => "mm",
+            Unit::Q => "q",
+            Unit::In => "in",
+            Unit::Pt => "pt",
+            Unit::Pc => "pc",
+            Unit::Deg => "deg",
+            Unit::Grad => "grad",
+            Unit::Rad => "rad",
+            Unit::Turn => "turn",
+            Unit::S => "s",
+            Unit::Ms => "ms",
+            Unit::Hz => "hz",
+            Unit::KHz => "khz",
+            Unit::Dpi => "dpi",
+            Unit::Dpcm => "dpcm",
+            Unit::Dppx => "dppx",
+            Unit::X => "x",
+            Unit::Fr => "fr",
+            Unit::Pct => "%",
+            Unit::Unknown(ref unit) => unit,
+        }
+    }
+
+    pub fn kind(&self) -> UnitKind {
         match self {
-            Unit::Em => write!(f, "{}", "em"),
-            Unit::Ex => write!(f, "{}", "ex"),
-            Unit::Ch => write!(f, "{}", "ch"),
-            Unit::Rem => write!(f, "{}", "rem"),
-            Unit::Vh => write!(f, "{}", "vh"),
-            Unit::Vw => write!(f, "{}", "vw"),
-            Unit::Vmin => write!(f, "{}", "vmin"),
-            Unit::Vmax => write!(f, "{}", "vmax"),
-            Unit::Px => write!(f, "{}", "px"),
-            Unit::Mm => write!(f, "{}", "mm"),
-            Unit::Q => write!(f, "{}", "q"),
-            Unit::Cm => write!(f, "{}", "cm"),
-            Unit::In => write!(f, "{}", "in"),
-            Unit::Pt => write!(f, "{}", "pt"),
-            Unit::Pc => write!(f, "{}", "pc"),
-            Unit::Pct => write!(f, "{}", "%"),
-            _ => write!(f, "{}", "px"),
+            Unit::Em
+            | Unit::Rem
+            | Unit::Ex
+            | Unit::Rex
+            | Unit::Cap
+            | Unit::Rcap
+            | Unit::Ch
+            | Unit::Rch
+            | Unit::Ic
+            | Unit::Ric
+            | Unit::Lh
+            | Unit::Rlh => UnitKind::Length,
+            Unit::Vw
+            | Unit::Vh
+            | Unit::Vi
+            | Unit::Vb
+            | Unit::Vmin
+            | Unit::Vmax
+            | Unit::Svw
+            | Unit::Svh
+            | Unit::Svi
+            | Unit::Svb
+            | Unit::Svmin
+            | Unit::Svmax
+            | Unit::Lvw
+            | Unit::Lvh
+            | Unit::Lvi
+            | Unit::Lvb
+            | Unit::Lvmin
+            | Unit::Lvmax
+            | Unit::Dvw
+            | Unit::Dvh
+            | Unit::Dvi
+            | Unit::Dvb
+            | Unit::Dvmin
+            | Unit::Dvmax => UnitKind::Length,
+            Unit::Cqw | Unit::Cqh | Unit::Cqi | Unit::Cqb | Unit::Cqmin | Unit::Cqmax => {
+                UnitKind::Length
+            }
+            Unit::Px | Unit::Cm | Unit::Mm | Unit::Q | Unit::In | Unit::Pt | Unit::Pc => {
+                UnitKind::Length
+            }
+            Unit::Deg | Unit::Grad | Unit::Rad | Unit::Turn => UnitKind::Angle,
+            Unit::S | Unit::Ms => UnitKind::Time,
+            Unit::Hz | Unit::KHz => UnitKind::Frequency,
+            Unit::Dpi | Unit::Dpcm | Unit::Dppx | Unit::X => UnitKind::Resolution,
+            Unit::Fr => UnitKind::Flex,
+            Unit::Pct => UnitKind::Percentage,
+            Unit::Unknown(_) => UnitKind::Unknown,
         }
     }
+
+    pub fn is_length(&self) -> bool {
+        self.kind() == UnitKind::Length
+    }
+
+    /// Factor converting this unit to the canonical unit of its kind: px, deg,
+    /// s, hz or dppx. `None` for units which depend on the context, like em or vw.
+    pub fn canonical(&self) -> Option<(f32, Unit)> {
+        let canonical = match self {
+            Unit::Px => (1.0, Unit::Px),
+            Unit::Cm => (96.0 / 2.54, Unit::Px),
+            Unit::Mm => (96.0 / 25.4, Unit::Px),
+            Unit::Q => (96.0 / 101.6, Unit::Px),
+            Unit::In => (96.0, Unit::Px),
+            Unit::Pt => (96.0 / 72.0, Unit::Px),
+            Unit::Pc => (16.0, Unit::Px),
+            Unit::Deg => (1.0, Unit::Deg),
+            Unit::Grad => (0.9, Unit::Deg),
+            Unit::Rad => (180.0 / std::f32::consts::PI, Unit::Deg),
+            Unit::Turn => (360.0, Unit::Deg),
+            Unit::S => (1.0, Unit::S),
+            Unit::Ms => (0.001, Unit::S),
+            Unit::Hz => (1.0, Unit::Hz),
+            Unit::KHz => (1000.0, Unit::Hz),
+            Unit::Dppx | Unit::X => (1.0, Unit::Dppx),
+            Unit::Dpi => (1.0 / 96.0, Unit::Dppx),
+            Unit::Dpcm => (2.54 / 96.0, Unit::Dppx),
+            _ => return None,
+        };
+
+        Some(canonical)
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
 }
 
 impl Stylesheet {
-    pub fn new(rules: Vec<Rule>) -> Stylesheet {
+    pub fn new(rules: Vec<CssRule>) -> Stylesheet {
         Stylesheet { rules }
     }
+
+    /// Style rules of the stylesheet, including the ones nested in @media and @supports
+    pub fn style_rules(&self) -> Vec<&Rule> {
+        let mut result = Vec::new();
+        collect_style_rules(&self.rules, &mut result);
+        result
+    }
 }
-impl Default for Stylesheet {
-    fn default() -> Self {
-        Stylesheet { rules: Vec::new() }
+
+fn collect_style_rules<'a>(rules: &'a [CssRule], result: &mut Vec<&'a Rule>) {
+    for rule in rules {
+        match rule {
+            CssRule::Style(rule) => result.push(rule),
+            CssRule::Media(rule) | CssRule::Supports(rule) => {
+                collect_style_rules(&rule.rules, result)
+            }
+            _ => {}
+        }
     }
 }
 impl fmt::Debug for Stylesheet {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut rule_result = String::new();
         for rule in &self.rules {
-            if rule_result.len() > 0 {
+            if !rule_result.is_empty() {
                 rule_result.push_str("\n\n");
             }
             rule_result.push_str(&format!("{:?}", rule));
@@ -98,7 +528,7 @@ impl fmt::Display for Stylesheet {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut rule_result = String::new();
         for rule in &self.rules {
-            if rule_result.len() > 0 {
+            if !rule_result.is_empty() {
                 rule_result.push_str("\r\n\r\n");
             }
             rule_result.push_str(&format!("{}", rule));
@@ -112,16 +542,13 @@ impl Rule {
         Rule {
             selectors,
             declarations,
+            span: Span::default(),
         }
     }
-}
 
-impl Default for Rule {
-    fn default() -> Self {
-        Rule {
-            selectors: Vec::new(),
-            declarations: Vec::new(),
-        }
+    /// Selector list as written in the stylesheet, e.g. `h1, h2`
+    pub fn selector_text(&self) -> String {
+        join(&self.selectors)
     }
 }
 
@@ -132,7 +559,7 @@ impl fmt::Debug for Rule {
         let tab = "    ";
 
         for selector in &self.selectors {
-            if sel_result.len() > 0 {
+            if !sel_result.is_empty() {
                 sel_result.push_str(", ");
             }
             sel_result.push_str(&format!("{:?}", selector));
@@ -155,7 +582,7 @@ impl fmt::Display for Rule {
         let tab = "    ";
 
         for selector in &self.selectors {
-            if sel_result.len() > 0 {
+            if !sel_result.is_empty() {
                 sel_result.push_str(", ");
             }
             sel_result.push_str(&format!("{}", selector));
@@ -172,29 +599,145 @@ impl fmt::Display for Rule {
     }
 }
 
+impl fmt::Display for CssRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CssRule::Style(rule) => write!(f, "{}", rule),
+            CssRule::Media(rule) => write!(f, "@media {}", rule),
+            CssRule::Supports(rule) => write!(f, "@supports {}", rule),
+            CssRule::Import(rule) => write!(f, "{}", rule),
+            CssRule::FontFace(rule) => write!(f, "{}", rule),
+            CssRule::Keyframes(rule) => write!(f, "{}", rule),
+            CssRule::Other(rule) => write!(f, "{}", rule),
+        }
+    }
+}
+
+impl ConditionalRule {
+    pub fn new(condition: String, rules: Vec<CssRule>) -> ConditionalRule {
+        ConditionalRule {
+            condition,
+            rules,
+            span: Span::default(),
+        }
+    }
+}
+
+impl fmt::Display for ConditionalRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rule_result = String::new();
+        for rule in &self.rules {
+            if !rule_result.is_empty() {
+                rule_result.push_str("\r\n\r\n");
+            }
+            rule_result.push_str(&indent(&format!("{}", rule)));
+        }
+
+        write!(f, "{} {{\r\n{}\r\n}}", self.condition, rule_result)
+    }
+}
+
+impl fmt::Display for ImportRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.conditions.is_empty() {
+            true => write!(f, "@import url(\"{}\");", self.url),
+            false => write!(f, "@import url(\"{}\") {};", self.url, self.conditions),
+        }
+    }
+}
+
+impl fmt::Display for FontFaceRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut decl_result = String::new();
+        let tab = "    ";
+
+        for declaration in &self.declarations {
+            decl_result.push_str(tab);
+            decl_result.push_str(&format!("{}", declaration));
+            decl_result.push('\r');
+            decl_result.push('\n');
+        }
+
+        write!(f, "@font-face {{\r\n{}}}", decl_result)
+    }
+}
+
+impl fmt::Display for KeyframesRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut keyframe_result = String::new();
+        for keyframe in &self.keyframes {
+            if !keyframe_result.is_empty() {
+                keyframe_result.push_str("\r\n\r\n");
+            }
+            keyframe_result.push_str(&indent(&format!("{}", keyframe)));
+        }
+
+        write!(
+            f,
+            "@{}keyframes {} {{\r\n{}\r\n}}",
+            self.vendor_prefix.as_deref().unwrap_or(""),
+            self.name,
+            keyframe_result
+        )
+    }
+}
+
+impl fmt::Display for Keyframe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut decl_result = String::new();
+        let tab = "    ";
+
+        for declaration in &self.declarations {
+            decl_result.push_str(tab);
+            decl_result.push_str(&format!("{}", declaration));
+            decl_result.push('\r');
+            decl_result.push('\n');
+        }
+
+        write!(f, "{} {{\r\n{}}}", self.selectors.join(", "), decl_result)
+    }
+}
+
+impl fmt::Display for AtRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let head = match self.prelude.is_empty() {
+            true => format!("@{}", self.name),
+            false => format!("@{} {}", self.name, self.prelude),
+        };
+
+        match self.block {
+            Some(ref block) => write!(f, "{} {{{}}}", head, block),
+            None => write!(f, "{};", head),
+        }
+    }
+}
+
 impl Selector {
-    pub fn new(simple: Vec<SimpleSelector>, combinators: Vec<char>) -> Selector {
+    pub fn new(simple: Vec<SimpleSelector>, combinators: Vec<Combinator>) -> Selector {
         Selector {
             simple,
             combinators,
+            span: Span::default(),
         }
     }
-}
-impl Default for Selector {
-    fn default() -> Self {
-        Selector {
-            simple: Vec::new(),
-            combinators: Vec::new(),
-        }
+
+    /// Specificity as (ids, classes, types), following Selectors Level 4
+    pub fn specificity(&self) -> Specificity {
+        self.simple
+            .iter()
+            .map(SimpleSelector::specificity)
+            .fold((0, 0, 0), add_specificity)
     }
 }
 impl fmt::Debug for Selector {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut result = String::new();
 
-        for sel in &self.simple {
-            if result.len() > 0 {
-                result.push_str(", ");
+        for (i, sel) in self.simple.iter().enumerate() {
+            if i > 0 {
+                if let Some(combinator) = self.combinators.get(i - 1) {
+                    result.push_str(&format!("{}", combinator));
+                }
             }
             result.push_str(&format!("{:?}", sel));
         }
@@ -207,9 +750,11 @@ impl fmt::Display for Selector {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut result = String::new();
 
-        for sel in &self.simple {
-            if result.len() > 0 {
-                result.push_str(", ");
+        for (i, sel) in self.simple.iter().enumerate() {
+            if i > 0 {
+                if let Some(combinator) = self.combinators.get(i - 1) {
+                    result.push_str(&format!("{}", combinator));
+                }
             }
             result.push_str(&format!("{}", sel));
         }
@@ -218,85 +763,271 @@ impl fmt::Display for Selector {
     }
 }
 
+impl fmt::Display for Combinator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Combinator::Descendant => write!(f, " "),
+            Combinator::Child => write!(f, " > "),
+            Combinator::NextSibling => write!(f, " + "),
+            Combinator::SubsequentSibling => write!(f, " ~ "),
+        }
+    }
+}
+
 impl SimpleSelector {
     pub fn new(
         tag_name: Option<String>,
         id: Option<String>,
         classes: Vec<String>,
     ) -> SimpleSelector {
-        SimpleSelector {
-            tag_name,
-            id,
-            classes,
-        }
+        let components = tag_name
+            .map(SelectorComponent::Type)
+            .into_iter()
+            .chain(id.map(SelectorComponent::Id))
+            .chain(classes.into_iter().map(SelectorComponent::Class))
+            .collect();
+
+        SimpleSelector { components }
+    }
+
+    pub fn universal(&self) -> bool {
+        self.components.contains(&SelectorComponent::Universal)
+    }
+
+    pub fn tag_name(&self) -> Option<&str> {
+        self.components
+            .iter()
+            .find_map(|component| match component {
+                SelectorComponent::Type(name) => Some(name.as_str()),
+                _ => None,
+            })
+    }
+
+    /// First id, a compound selector with several ids never matches
+    pub fn id(&self) -> Option<&str> {
+        self.components
+            .iter()
+            .find_map(|component| match component {
+                SelectorComponent::Id(id) => Some(id.as_str()),
+                _ => None,
+            })
+    }
+
+    pub fn classes(&self) -> Vec<&str> {
+        self.components
+            .iter()
+            .filter_map(|component| match component {
+                SelectorComponent::Class(class) => Some(class.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn attributes(&self) -> Vec<&AttributeSelector> {
+        self.components
+            .iter()
+            .filter_map(|component| match component {
+                SelectorComponent::Attribute(attribute) => Some(attribute),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn pseudo_classes(&self) -> Vec<&PseudoClass> {
+        self.components
+            .iter()
+            .filter_map(|component| match component {
+                SelectorComponent::PseudoClass(pseudo_class) => Some(pseudo_class),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn pseudo_element(&self) -> Option<&PseudoElement> {
+        self.components
+            .iter()
+            .find_map(|component| match component {
+                SelectorComponent::PseudoElement(pseudo_element) => Some(pseudo_element),
+                _ => None,
+            })
+    }
+
+    pub fn specificity(&self) -> Specificity {
+        self.components.iter().fold((0, 0, 0), |result, component| {
+            let specificity = match component {
+                SelectorComponent::Universal => (0, 0, 0),
+                SelectorComponent::Id(_) => (1, 0, 0),
+                SelectorComponent::Class(_) | SelectorComponent::Attribute(_) => (0, 1, 0),
+                SelectorComponent::Type(_) | SelectorComponent::PseudoElement(_) => (0, 0, 1),
+                SelectorComponent::PseudoClass(pseudo_class) => pseudo_class.specificity(),
+            };
+
+            add_specificity(result, specificity)
+        })
     }
 }
 
-impl Default for SimpleSelector {
-    fn default() -> Self {
-        SimpleSelector {
-            tag_name: None,
-            id: None,
-            classes: Vec::new(),
+impl PseudoClass {
+    /// `:is()`, `:not()` and `:has()` count as their most specific argument,
+    /// `:where()` counts as nothing
+    pub fn specificity(&self) -> Specificity {
+        match (self.name.as_str(), &self.argument) {
+            ("where", _) => (0, 0, 0),
+            (_, Some(PseudoClassArgument::Selectors(selectors))) => match self.name.as_str() {
+                "host" | "host-context" => add_specificity((0, 1, 0), max_specificity(selectors)),
+                _ => max_specificity(selectors),
+            },
+            (_, Some(PseudoClassArgument::Relative(selectors))) => selectors
+                .iter()
+                .map(|(_, selector)| selector.specificity())
+                .max()
+                .unwrap_or((0, 0, 0)),
+            (_, Some(PseudoClassArgument::Nth { of, .. })) => {
+                add_specificity((0, 1, 0), max_specificity(of))
+            }
+            _ => (0, 1, 0),
         }
     }
 }
 
 impl fmt::Debug for SimpleSelector {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut result = String::new();
+        write!(f, "{}", self)
+    }
+}
 
-        match self.tag_name {
-            Some(ref t) => result.push_str(t),
-            None => {}
+impl fmt::Display for SimpleSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for component in &self.components {
+            write!(f, "{}", component)?;
         }
 
-        match self.id {
-            Some(ref s) => {
-                result.push('#');
-                result.push_str(s);
-            }
-            None => {}
-        }
+        Ok(())
+    }
+}
 
-        for class in &self.classes {
-            result.push('.');
-            result.push_str(class);
+impl fmt::Display for SelectorComponent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SelectorComponent::Universal => write!(f, "*"),
+            SelectorComponent::Type(name) => write!(f, "{}", name),
+            SelectorComponent::Id(id) => write!(f, "#{}", id),
+            SelectorComponent::Class(class) => write!(f, ".{}", class),
+            SelectorComponent::Attribute(attribute) => write!(f, "{}", attribute),
+            SelectorComponent::PseudoClass(pseudo_class) => write!(f, "{}", pseudo_class),
+            SelectorComponent::PseudoElement(pseudo_element) => write!(f, "{}", pseudo_element),
         }
+    }
+}
 
-        write!(f, "{}", result)
+impl fmt::Display for AttributeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.matcher {
+            Some(ref matcher) => write!(f, "[{}{}]", self.name, matcher),
+            None => write!(f, "[{}]", self.name),
+        }
     }
 }
 
-impl fmt::Display for SimpleSelector {
+impl fmt::Display for AttributeMatcher {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut result = String::new();
+        let operator = match self.operator {
+            AttributeOperator::Equals => "=",
+            AttributeOperator::Includes => "~=",
+            AttributeOperator::DashMatch => "|=",
+            AttributeOperator::Prefix => "^=",
+            AttributeOperator::Suffix => "$=",
+            AttributeOperator::Substring => "*=",
+        };
+        let value = self.value.replace('\\', "\\\\").replace('"', "\\\"");
 
-        match self.tag_name {
-            Some(ref t) => result.push_str(t),
-            None => {}
+        match self.case_sensitivity {
+            Some(CaseSensitivity::Insensitive) => write!(f, "{}\"{}\" i", operator, value),
+            Some(CaseSensitivity::Sensitive) => write!(f, "{}\"{}\" s", operator, value),
+            None => write!(f, "{}\"{}\"", operator, value),
         }
+    }
+}
 
-        match self.id {
-            Some(ref s) => {
-                result.push('#');
-                result.push_str(s);
-            }
-            None => {}
+impl fmt::Display for PseudoClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.argument {
+            Some(ref argument) => write!(f, ":{}({})", self.name, argument),
+            None => write!(f, ":{}", self.name),
         }
+    }
+}
+
+impl fmt::Display for PseudoClassArgument {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PseudoClassArgument::Selectors(selectors) => write!(f, "{}", join(selectors)),
+            PseudoClassArgument::Relative(selectors) => {
+                let mut result = String::new();
 
-        for class in &self.classes {
-            result.push('.');
-            result.push_str(class);
+                for (combinator, selector) in selectors {
+                    if !result.is_empty() {
+                        result.push_str(", ");
+                    }
+                    if *combinator != Combinator::Descendant {
+                        result.push_str(format!("{}", combinator).trim_start());
+                    }
+                    result.push_str(&format!("{}", selector));
+                }
+
+                write!(f, "{}", result)
+            }
+            PseudoClassArgument::Nth { a, b, of } => {
+                let mut result = match (a, b) {
+                    (0, b) => b.to_string(),
+                    (1, 0) => "n".to_string(),
+                    (-1, 0) => "-n".to_string(),
+                    (a, 0) => format!("{}n", a),
+                    (1, b) => format!("n{:+}", b),
+                    (-1, b) => format!("-n{:+}", b),
+                    (a, b) => format!("{}n{:+}", a, b),
+                };
+
+                if !of.is_empty() {
+                    result.push_str(&format!(" of {}", join(of)));
+                }
+
+                write!(f, "{}", result)
+            }
+            PseudoClassArgument::Other(argument) => write!(f, "{}", argument),
         }
+    }
+}
 
-        write!(f, "{}", result)
+impl fmt::Display for PseudoElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.argument {
+            Some(ref argument) => write!(f, "::{}({})", self.name, argument),
+            None => write!(f, "::{}", self.name),
+        }
     }
 }
 
 impl Declaration {
     pub fn new(property: String, value: Value) -> Declaration {
-        Declaration { property, value }
+        Declaration {
+            raw_property: property.clone(),
+            property,
+            raw_value: format!("{}", value),
+            value,
+            important: false,
+            span: Span::default(),
+            value_span: Span::default(),
+        }
+    }
+
+    /// Value with case-insensitive parts lowercased, see [`normalize_case`].
+    /// Values of custom properties are case-sensitive and stay as written.
+    pub fn normalized_value(&self) -> String {
+        match self.property.starts_with("--") {
+            true => self.raw_value.clone(),
+            false => normalize_case(&self.raw_value, has_custom_idents(&self.property)),
+        }
     }
 }
 
@@ -304,20 +1035,93 @@ impl Default for Declaration {
     fn default() -> Self {
         Declaration {
             property: String::from(""),
+            raw_property: String::from(""),
             value: Value::Other(String::from("")),
+            raw_value: String::from(""),
+            important: false,
+            span: Span::default(),
+            value_span: Span::default(),
         }
     }
 }
 
 impl fmt::Debug for Declaration {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}: {:?}", self.property, self.value)
+        match self.important {
+            true => write!(f, "{}: {:?} !important", self.property, self.value),
+            false => write!(f, "{}: {:?}", self.property, self.value),
+        }
     }
 }
 
 impl fmt::Display for Declaration {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}: {};", self.property, self.value)
+        match self.important {
+            true => write!(f, "{}: {} !important;", self.property, self.value),
+            false => write!(f, "{}: {};", self.property, self.value),
+        }
+    }
+}
+
+impl Number {
+    pub fn new(value: f32, repr: &str) -> Number {
+        Number {
+            value,
+            repr: repr.to_string(),
+        }
+    }
+}
+
+impl From<f32> for Number {
+    fn from(value: f32) -> Number {
+        Number {
+            value,
+            repr: String::new(),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Number) -> bool {
+        self.value == other.value
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.repr.is_empty() {
+            true => write!(f, "{}", self.value),
+            false => write!(f, "{}", self.repr),
+        }
+    }
+}
+
+impl Value {
+    /// Colors in the value, including ones nested in functions and lists
+    pub fn colors(&self) -> Vec<&Color> {
+        match self {
+            Value::Color(color) => vec![color],
+            Value::Function { arguments, .. } => arguments.iter().flat_map(Value::colors).collect(),
+            Value::List { items, .. } => items.iter().flat_map(Value::colors).collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Length in px, `None` if the value is not a length or can't be resolved
+    /// in `context`, e.g. a percentage without a basis or a `var()`
+    pub fn to_px(&self, context: &LengthContext) -> Option<f32> {
+        match self {
+            Value::Length(number, unit) => context.to_px(number.value, unit),
+            Value::Percentage(number) => context.to_px(number.value, &Unit::Pct),
+            // Unitless zero is a valid length
+            Value::Integer(0) => Some(0.0),
+            Value::Number(number) if number.value == 0.0 => Some(0.0),
+            Value::Calc(calc) => match calc.evaluate(context)? {
+                Value::Length(number, Unit::Px) => Some(number.value),
+                _ => None,
+            },
+            _ => None,
+        }
     }
 }
 
@@ -325,8 +1129,10 @@ impl fmt::Debug for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Value::Color(ref c) => write!(f, "{:?}", c),
-            Value::Length(l, _) => write!(f, "{:?}", l),
+            Value::Length(ref l, _) => write!(f, "{:?}", l.value),
+            Value::Custom { ref text, .. } => write!(f, "{:?}", text),
             Value::Other(ref s) => write!(f, "{:?}", s),
+            _ => write!(f, "{:?}", format!("{}", self)),
         }
     }
 }
@@ -334,13 +1140,124 @@ impl fmt::Debug for Value {
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Value::Keyword(ref k) => write!(f, "{}", k),
+            Value::Number(ref n) => write!(f, "{}", n),
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Percentage(ref n) => write!(f, "{}%", n),
+            Value::Length(ref l, ref u) => write!(f, "{}{}", l, u),
+            Value::Dimension(ref n, ref u) => write!(f, "{}{}", n, u),
+            Value::String(ref s) => write!(f, "{}", quote(s)),
+            Value::Url(ref url) => write!(f, "url({})", url),
             Value::Color(ref c) => write!(f, "{}", c),
-            Value::Length(l, u) => write!(f, "{}{}", l, u),
+            Value::Function {
+                ref name,
+                ref arguments,
+            } => write!(f, "{}({})", name, join_values(arguments, Separator::Comma)),
+            Value::Calc(ref calc) => write!(f, "{}", calc),
+            Value::List {
+                separator,
+                ref items,
+            } => write!(f, "{}", join_values(items, *separator)),
+            Value::Custom { ref text, .. } => write!(f, "{}", text),
+            Value::Var {
+                ref name,
+                fallback: Some(ref fallback),
+            } => write!(f, "var({}, {})", name, fallback),
+            Value::Var { ref name, .. } => write!(f, "var({})", name),
             Value::Other(ref s) => write!(f, "{}", s),
         }
     }
 }
 
+/// Whether identifiers in the value of a property are author-defined names,
+/// which are case-sensitive, e.g. `animation-name: FadeIn`
+fn has_custom_idents(property: &str) -> bool {
+    matches!(
+        property,
+        "animation"
+            | "animation-name"
+            | "container"
+            | "container-name"
+            | "counter-increment"
+            | "counter-reset"
+            | "counter-set"
+            | "grid-area"
+            | "grid-column"
+            | "grid-column-end"
+            | "grid-column-start"
+            | "grid-row"
+            | "grid-row-end"
+            | "grid-row-start"
+            | "view-transition-name"
+    )
+}
+
+fn add_specificity(a: Specificity, b: Specificity) -> Specificity {
+    (a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+
+fn max_specificity(selectors: &[Selector]) -> Specificity {
+    selectors
+        .iter()
+        .map(Selector::specificity)
+        .max()
+        .unwrap_or((0, 0, 0))
+}
+
+/// Values joined by a separator, nested lists are wrapped with their own separator
+fn join_values(values: &[Value], separator: Separator) -> String {
+    let separator = match separator {
+        Separator::Space => " ",
+        Separator::Slash => " / ",
+        Separator::Comma => ", ",
+    };
+
+    values
+        .iter()
+        .map(|value| format!("{}", value))
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+/// Double-quoted css string
+fn quote(text: &str) -> String {
+    let mut result = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => {
+                result.push('\\');
+                result.push(c);
+            }
+            '\n' => result.push_str("\\a "),
+            _ => result.push(c),
+        }
+    }
+    result.push('"');
+
+    result
+}
+
+/// Selector list separated by commas
+fn join(selectors: &[Selector]) -> String {
+    selectors
+        .iter()
+        .map(|selector| format!("{}", selector))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Shifts every non-empty line one level to the right
+fn indent(text: &str) -> String {
+    text.split("\r\n")
+        .map(|line| match line.is_empty() {
+            true => String::new(),
+            false => format!("    {}", line),
+        })
+        .collect::<Vec<String>>()
+        .join("\r\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -367,23 +1284,82 @@ mod tests {
 
     #[test]
     fn length() {
-        assert_eq!("1em", format!("{}", Value::Length(1.0, Unit::Em)));
-        assert_eq!("1.5em", format!("{}", Value::Length(1.5, Unit::Em)));
-        assert_eq!("20%", format!("{}", Value::Length(20.0, Unit::Pct)));
-        // assert_eq!("ex", format!("{}", Unit::Ex));
-        // assert_eq!("ch", format!("{}", Unit::Ch));
-        // assert_eq!("rem", format!("{}", Unit::Rem));
-        // assert_eq!("vh", format!("{}", Unit::Vh));
-        // assert_eq!("vw", format!("{}", Unit::Vw));
-        // assert_eq!("vmin", format!("{}", Unit::Vmin));
-        // assert_eq!("vmax", format!("{}", Unit::Vmax));
-        // assert_eq!("px", format!("{}", Unit::Px));
-        // assert_eq!("mm", format!("{}", Unit::Mm));
-        // assert_eq!("q", format!("{}", Unit::Q));
-        // assert_eq!("cm", format!("{}", Unit::Cm));
-        // assert_eq!("in", format!("{}", Unit::In));
-        // assert_eq!("pt", format!("{}", Unit::Pt));
-        // assert_eq!("pc", format!("{}", Unit::Pc));
-        // assert_eq!("%", format!("{}", Unit::Pct));
+        assert_eq!("1em", format!("{}", Value::Length(1.0.into(), Unit::Em)));
+        assert_eq!("1.5em", format!("{}", Value::Length(1.5.into(), Unit::Em)));
+        assert_eq!("20%", format!("{}", Value::Percentage(20.0.into())));
+    }
+
+    #[test]
+    fn unit_new() {
+        let tests = [
+            ("dvh", Unit::Dvh, UnitKind::Length),
+            ("svmin", Unit::Svmin, UnitKind::Length),
+            ("cqw", Unit::Cqw, UnitKind::Length),
+            ("rlh", Unit::Rlh, UnitKind::Length),
+            ("fr", Unit::Fr, UnitKind::Flex),
+            ("deg", Unit::Deg, UnitKind::Angle),
+            ("turn", Unit::Turn, UnitKind::Angle),
+            ("ms", Unit::Ms, UnitKind::Time),
+            ("khz", Unit::KHz, UnitKind::Frequency),
+            ("dppx", Unit::Dppx, UnitKind::Resolution),
+            ("x", Unit::X, UnitKind::Resolution),
+            ("%", Unit::Pct, UnitKind::Percentage),
+            ("foo", Unit::Unknown("foo".to_string()), UnitKind::Unknown),
+        ];
+
+        for (text, unit, kind) in tests {
+            assert_eq!(Unit::new(text), unit, "Parse \"{}\"", text);
+            assert_eq!(unit.kind(), kind, "kind of {}", text);
+            assert_eq!(format!("{}", unit), text);
+        }
+    }
+
+    #[test]
+    fn dimension() {
+        assert_eq!(
+            "45deg",
+            format!("{}", Value::Dimension(45.0.into(), Unit::Deg))
+        );
+        assert_eq!(
+            "2foo",
+            format!(
+                "{}",
+                Value::Dimension(2.0.into(), Unit::Unknown("foo".to_string()))
+            )
+        );
+    }
+
+    #[test]
+    fn value_to_px() {
+        let context = LengthContext {
+            percentage_basis: Some(320.0),
+            ..Default::default()
+        };
+        let tests = [
+            (Value::Length(1.0.into(), Unit::In), Some(96.0)),
+            (Value::Length(96.0.into(), Unit::Px), Some(96.0)),
+            (Value::Length(2.0.into(), Unit::Rem), Some(32.0)),
+            (Value::Percentage(50.0.into()), Some(160.0)),
+            (Value::Integer(0), Some(0.0)),
+            (Value::Integer(1), None),
+            (Value::Dimension(1.0.into(), Unit::Fr), None),
+            (Value::Keyword("auto".to_string()), None),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(value.to_px(&context), expected, "to_px {}", value);
+        }
+
+        let lengths = [
+            "em", "rem", "ex", "rex", "cap", "rcap", "ch", "rch", "ic", "ric", "lh", "rlh", "vw",
+            "vh", "vi", "vb", "vmin", "vmax", "svw", "svh", "svi", "svb", "svmin", "svmax", "lvw",
+            "lvh", "lvi", "lvb", "lvmin", "lvmax", "dvw", "dvh", "dvi", "dvb", "dvmin", "dvmax",
+            "cqw", "cqh", "cqi", "cqb", "cqmin", "cqmax", "px", "cm", "mm", "q", "in", "pt", "pc",
+        ];
+
+        for unit in lengths {
+            let value = Value::Length(1.0.into(), Unit::new(unit));
+            assert!(value.to_px(&context).is_some(), "to_px {}", value);
+        }
     }
 }
@@ -0,0 +1,817 @@
+/// Tokens produced by the tokenization stage of CSS Syntax Level 3
+/// (https://www.w3.org/TR/css-syntax-3/#tokenization).
+#[derive(PartialEq, Clone, Debug)]
+pub enum Token {
+    /// Example: `color`
+    Ident(String),
+    /// Example: `rgb(`, the value holds the name without the parenthesis
+    Function(String),
+    /// Example: `@media`, the value holds the name without the `@`
+    AtKeyword(String),
+    /// Example: `#page`
+    Hash(String, HashType),
+    /// Example: `"Hello"`, the value holds the unescaped content without quotes
+    QuotedString(String),
+    /// A string interrupted by a newline
+    BadString,
+    /// Example: `url(image.png)`, the value holds the unescaped content
+    Url(String),
+    /// An unquoted url containing invalid characters
+    BadUrl,
+    /// Any other single code point, e.g. `.`, `>` or `!`
+    Delim(char),
+    /// Example: `-1.5`
+    Number(f32, NumberType),
+    /// Example: `20%`
+    Percentage(f32),
+    /// Example: `10px`
+    Dimension(f32, NumberType, String),
+    Whitespace,
+    /// `<!--`
+    Cdo,
+    /// `-->`
+    Cdc,
+    Colon,
+    Semicolon,
+    Comma,
+    OpenSquare,
+    CloseSquare,
+    OpenParen,
+    CloseParen,
+    OpenCurly,
+    CloseCurly,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum HashType {
+    /// The hash value is a valid identifier and can be used as an id selector
+    Id,
+    Unrestricted,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum NumberType {
+    Integer,
+    Number,
+}
+
+/// Splits a css string into tokens.
+///
+/// Comments are consumed silently, the way the specification requires.
+/// Newline normalization is done on the fly so that `position()` always
+/// refers to byte offsets of the original input.
+pub struct Tokenizer<'a> {
+    input: &'a str,
+    position: usize,
+    token_start: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(input: &'a str) -> Tokenizer<'a> {
+        Tokenizer {
+            input,
+            position: 0,
+            token_start: 0,
+        }
+    }
+
+    /// Byte offset of the next code point to be consumed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Byte offset where the last returned token starts.
+    pub fn token_start(&self) -> usize {
+        self.token_start
+    }
+
+    pub fn next_token(&mut self) -> Option<Token> {
+        self.consume_comments();
+        self.token_start = self.position;
+
+        let c = self.next_char()?;
+
+        let token = match c {
+            c if is_whitespace(c) => {
+                self.consume_whitespace();
+                Token::Whitespace
+            }
+            '"' | '\'' => self.consume_string(c),
+            '#' => {
+                if self.peek(0).is_some_and(is_ident_char) || self.starts_escape(0) {
+                    let hash_type = if self.starts_ident(0) {
+                        HashType::Id
+                    } else {
+                        HashType::Unrestricted
+                    };
+
+                    Token::Hash(self.consume_ident_sequence(), hash_type)
+                } else {
+                    Token::Delim(c)
+                }
+            }
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '+' | '.' if self.starts_number_with(c) => {
+                self.reconsume(c);
+                self.consume_numeric()
+            }
+            ',' => Token::Comma,
+            '-' => {
+                if self.starts_number_with(c) {
+                    self.reconsume(c);
+                    self.consume_numeric()
+                } else if self.peek(0) == Some('-') && self.peek(1) == Some('>') {
+                    self.next_char();
+                    self.next_char();
+                    Token::Cdc
+                } else if self.starts_ident_with(c) {
+                    self.reconsume(c);
+                    self.consume_ident_like()
+                } else {
+                    Token::Delim(c)
+                }
+            }
+            ':' => Token::Colon,
+            ';' => Token::Semicolon,
+            '<' => {
+                if self.peek(0) == Some('!')
+                    && self.peek(1) == Some('-')
+                    && self.peek(2) == Some('-')
+                {
+                    self.next_char();
+                    self.next_char();
+                    self.next_char();
+                    Token::Cdo
+                } else {
+                    Token::Delim(c)
+                }
+            }
+            '@' => {
+                if self.starts_ident(0) {
+                    Token::AtKeyword(self.consume_ident_sequence())
+                } else {
+                    Token::Delim(c)
+                }
+            }
+            '[' => Token::OpenSquare,
+            ']' => Token::CloseSquare,
+            '{' => Token::OpenCurly,
+            '}' => Token::CloseCurly,
+            '\\' => {
+                if self.starts_ident_with(c) {
+                    self.reconsume(c);
+                    self.consume_ident_like()
+                } else {
+                    Token::Delim(c)
+                }
+            }
+            c if c.is_ascii_digit() => {
+                self.reconsume(c);
+                self.consume_numeric()
+            }
+            c if is_ident_start(c) => {
+                self.reconsume(c);
+                self.consume_ident_like()
+            }
+            c => Token::Delim(c),
+        };
+
+        Some(token)
+    }
+
+    fn peek(&self, n: usize) -> Option<char> {
+        self.input[self.position..].chars().nth(n)
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.position += c.len_utf8();
+
+        // "\r\n" is a single newline
+        if c == '\r' && self.peek(0) == Some('\n') {
+            self.position += 1;
+            return Some('\n');
+        }
+
+        Some(c)
+    }
+
+    fn reconsume(&mut self, c: char) {
+        self.position -= c.len_utf8();
+    }
+
+    fn consume_comments(&mut self) {
+        while self.input[self.position..].starts_with("/*") {
+            match self.input[self.position + 2..].find("*/") {
+                Some(end) => self.position += end + 4,
+                None => self.position = self.input.len(),
+            }
+        }
+    }
+
+    fn consume_whitespace(&mut self) {
+        while self.peek(0).is_some_and(is_whitespace) {
+            self.next_char();
+        }
+    }
+
+    fn consume_string(&mut self, ending: char) -> Token {
+        let mut value = String::new();
+
+        loop {
+            match self.peek(0) {
+                None => return Token::QuotedString(value),
+                Some(c) if c == ending => {
+                    self.next_char();
+                    return Token::QuotedString(value);
+                }
+                Some(c) if is_newline(c) => return Token::BadString,
+                Some('\\') => {
+                    self.next_char();
+                    match self.peek(0) {
+                        None => {}
+                        Some(c) if is_newline(c) => {
+                            self.next_char();
+                        }
+                        Some(_) => value.push(self.consume_escaped()),
+                    }
+                }
+                Some(_) => value.push(self.next_char().unwrap()),
+            }
+        }
+    }
+
+    fn consume_numeric(&mut self) -> Token {
+        let (number, number_type) = self.consume_number();
+
+        if self.starts_ident(0) {
+            Token::Dimension(number, number_type, self.consume_ident_sequence())
+        } else if self.peek(0) == Some('%') {
+            self.next_char();
+            Token::Percentage(number)
+        } else {
+            Token::Number(number, number_type)
+        }
+    }
+
+    fn consume_number(&mut self) -> (f32, NumberType) {
+        let mut repr = String::new();
+        let mut number_type = NumberType::Integer;
+
+        if let Some(c @ '+') | Some(c @ '-') = self.peek(0) {
+            repr.push(c);
+            self.next_char();
+        }
+        self.consume_digits(&mut repr);
+
+        if self.peek(0) == Some('.') && self.peek(1).is_some_and(|c| c.is_ascii_digit()) {
+            repr.push('.');
+            self.next_char();
+            self.consume_digits(&mut repr);
+            number_type = NumberType::Number;
+        }
+
+        let exponent = match (self.peek(0), self.peek(1), self.peek(2)) {
+            (Some('e'), Some(d), _) | (Some('E'), Some(d), _) if d.is_ascii_digit() => 2,
+            (Some('e'), Some('+'), Some(d))
+            | (Some('e'), Some('-'), Some(d))
+            | (Some('E'), Some('+'), Some(d))
+            | (Some('E'), Some('-'), Some(d))
+                if d.is_ascii_digit() =>
+            {
+                3
+            }
+            _ => 0,
+        };
+        if exponent > 0 {
+            for _ in 1..exponent {
+                repr.push(self.next_char().unwrap());
+            }
+            self.consume_digits(&mut repr);
+            number_type = NumberType::Number;
+        }
+
+        (repr.parse::<f64>().unwrap_or(0.) as f32, number_type)
+    }
+
+    fn consume_digits(&mut self, repr: &mut String) {
+        while let Some(c) = self.peek(0).filter(|c| c.is_ascii_digit()) {
+            repr.push(c);
+            self.next_char();
+        }
+    }
+
+    fn consume_ident_like(&mut self) -> Token {
+        let name = self.consume_ident_sequence();
+
+        if self.peek(0) != Some('(') {
+            return Token::Ident(name);
+        }
+        self.next_char();
+
+        if !name.eq_ignore_ascii_case("url") {
+            return Token::Function(name);
+        }
+
+        // Whitespace before a quoted url belongs to the function arguments
+        while self.peek(0).is_some_and(is_whitespace) && self.peek(1).is_some_and(is_whitespace) {
+            self.next_char();
+        }
+        let next = match self.peek(0) {
+            Some(c) if is_whitespace(c) => self.peek(1),
+            c => c,
+        };
+
+        match next {
+            Some('"') | Some('\'') => Token::Function(name),
+            _ => self.consume_url(),
+        }
+    }
+
+    fn consume_url(&mut self) -> Token {
+        let mut value = String::new();
+
+        self.consume_whitespace();
+
+        loop {
+            match self.next_char() {
+                None | Some(')') => return Token::Url(value),
+                Some(c) if is_whitespace(c) => {
+                    self.consume_whitespace();
+
+                    return match self.peek(0) {
+                        None => Token::Url(value),
+                        Some(')') => {
+                            self.next_char();
+                            Token::Url(value)
+                        }
+                        Some(_) => {
+                            self.consume_bad_url_remnants();
+                            Token::BadUrl
+                        }
+                    };
+                }
+                Some('"') | Some('\'') | Some('(') => {
+                    self.consume_bad_url_remnants();
+                    return Token::BadUrl;
+                }
+                Some(c) if is_non_printable(c) => {
+                    self.consume_bad_url_remnants();
+                    return Token::BadUrl;
+                }
+                Some('\\') => {
+                    if self.starts_escape_with('\\') {
+                        value.push(self.consume_escaped());
+                    } else {
+                        self.consume_bad_url_remnants();
+                        return Token::BadUrl;
+                    }
+                }
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn consume_bad_url_remnants(&mut self) {
+        loop {
+            match self.next_char() {
+                None | Some(')') => return,
+                Some('\\') if self.starts_escape_with('\\') => {
+                    self.consume_escaped();
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// Consumes an escaped code point, assuming the `\` is already consumed
+    fn consume_escaped(&mut self) -> char {
+        match self.next_char() {
+            None => '\u{FFFD}',
+            Some(c) if c.is_ascii_hexdigit() => {
+                let mut hex = c.to_string();
+
+                while hex.len() < 6 {
+                    match self.peek(0) {
+                        Some(c) if c.is_ascii_hexdigit() => {
+                            hex.push(c);
+                            self.next_char();
+                        }
+                        _ => break,
+                    }
+                }
+                if self.peek(0).is_some_and(is_whitespace) {
+                    self.next_char();
+                }
+
+                match u32::from_str_radix(&hex, 16) {
+                    Ok(0) => '\u{FFFD}',
+                    Ok(code) => std::char::from_u32(code).unwrap_or('\u{FFFD}'),
+                    Err(_) => '\u{FFFD}',
+                }
+            }
+            Some(c) => c,
+        }
+    }
+
+    fn consume_ident_sequence(&mut self) -> String {
+        let mut result = String::new();
+
+        loop {
+            match self.peek(0) {
+                Some(c) if is_ident_char(c) => {
+                    result.push(c);
+                    self.next_char();
+                }
+                Some('\\') if self.starts_escape(0) => {
+                    self.next_char();
+                    result.push(self.consume_escaped());
+                }
+                _ => return result,
+            }
+        }
+    }
+
+    /// Checks if the code points starting at `n` are a valid escape
+    fn starts_escape(&self, n: usize) -> bool {
+        self.peek(n) == Some('\\') && self.peek(n + 1).is_some_and(|c| !is_newline(c))
+    }
+
+    /// Same as `starts_escape`, for an already consumed first code point
+    fn starts_escape_with(&self, first: char) -> bool {
+        first == '\\' && self.peek(0).is_some_and(|c| !is_newline(c))
+    }
+
+    /// Checks if the code points starting at `n` would start an identifier
+    fn starts_ident(&self, n: usize) -> bool {
+        match self.peek(n) {
+            Some('-') => {
+                self.peek(n + 1)
+                    .is_some_and(|c| is_ident_start(c) || c == '-')
+                    || self.starts_escape(n + 1)
+            }
+            Some('\\') => self.starts_escape(n),
+            Some(c) => is_ident_start(c),
+            None => false,
+        }
+    }
+
+    /// Same as `starts_ident`, for an already consumed first code point
+    fn starts_ident_with(&self, first: char) -> bool {
+        match first {
+            '-' => {
+                self.peek(0).is_some_and(|c| is_ident_start(c) || c == '-') || self.starts_escape(0)
+            }
+            '\\' => self.starts_escape_with(first),
+            c => is_ident_start(c),
+        }
+    }
+
+    /// Checks if an already consumed code point and the following ones
+    /// would start a number
+    fn starts_number_with(&self, first: char) -> bool {
+        let is_digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+
+        match first {
+            '+' | '-' => {
+                is_digit(self.peek(0)) || (self.peek(0) == Some('.') && is_digit(self.peek(1)))
+            }
+            '.' => is_digit(self.peek(0)),
+            c => c.is_ascii_digit(),
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        self.next_token()
+    }
+}
+
+fn is_newline(c: char) -> bool {
+    c == '\n' || c == '\r' || c == '\u{000C}'
+}
+
+fn is_whitespace(c: char) -> bool {
+    is_newline(c) || c == '\t' || c == ' '
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c >= '\u{0080}' || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    is_ident_start(c) || c.is_ascii_digit() || c == '-'
+}
+
+fn is_non_printable(c: char) -> bool {
+    ('\u{0000}'..='\u{0008}').contains(&c)
+        || c == '\u{000B}'
+        || ('\u{000E}'..='\u{001F}').contains(&c)
+        || c == '\u{007F}'
+}
+
+pub fn tokenize(input: &str) -> Vec<Token> {
+    Tokenizer::new(input).collect()
+}
+
+/// Lowercases the parts of `input` where css is case-insensitive: keywords,
+/// function and at-rule names, hex colors, numbers and units. Strings, urls,
+/// comments, dashed idents like `--brand` and the arguments of `var()` keep
+/// their original text.
+///
+/// With `custom_idents`, identifiers are author-defined names, e.g. in
+/// `animation-name: FadeIn`, and only CSS-wide keywords and `none` are lowercased.
+pub fn normalize_case(input: &str, custom_idents: bool) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut tokenizer = Tokenizer::new(input);
+    let mut last_end = 0;
+    // Nesting depth of blocks inside a var(), 0 outside of it
+    let mut var_depth = 0;
+
+    while let Some(token) = tokenizer.next_token() {
+        let start = tokenizer.token_start();
+        let text = &input[start..tokenizer.position()];
+
+        result.push_str(&input[last_end..start]);
+        last_end = tokenizer.position();
+
+        if var_depth > 0 {
+            match token {
+                Token::Function(_) | Token::OpenParen => var_depth += 1,
+                Token::CloseParen => var_depth -= 1,
+                _ => {}
+            }
+            result.push_str(text);
+            continue;
+        }
+
+        match token {
+            Token::Ident(ref name) if name.starts_with("--") => result.push_str(text),
+            Token::Ident(ref name)
+                if custom_idents
+                    && !matches!(
+                        name.to_lowercase().as_str(),
+                        "inherit" | "initial" | "unset" | "revert" | "revert-layer" | "none"
+                    ) =>
+            {
+                result.push_str(text)
+            }
+            Token::Hash(ref hash, _)
+                if !matches!(hash.len(), 3 | 4 | 6 | 8)
+                    || !hash.chars().all(|c| c.is_ascii_hexdigit()) =>
+            {
+                result.push_str(text)
+            }
+            Token::Function(ref name) if name.eq_ignore_ascii_case("var") => {
+                var_depth = 1;
+                result.push_str(&text.to_lowercase());
+            }
+            Token::Ident(_)
+            | Token::Function(_)
+            | Token::AtKeyword(_)
+            | Token::Hash(_, _)
+            | Token::Number(_, _)
+            | Token::Percentage(_)
+            | Token::Dimension(_, _, _) => result.push_str(&text.to_lowercase()),
+            Token::Url(_) | Token::BadUrl => match text.get(..4) {
+                Some(name) if name.eq_ignore_ascii_case("url(") => {
+                    result.push_str("url(");
+                    result.push_str(&text[4..]);
+                }
+                _ => result.push_str(text),
+            },
+            _ => result.push_str(text),
+        }
+    }
+    result.push_str(&input[last_end..]);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_empty() {
+        assert_eq!(tokenize(""), vec![]);
+        assert_eq!(tokenize("/* only comment */"), vec![]);
+    }
+
+    #[test]
+    fn tokenize_rule() {
+        assert_eq!(
+            tokenize("body {color: red;}"),
+            vec![
+                Token::Ident("body".to_string()),
+                Token::Whitespace,
+                Token::OpenCurly,
+                Token::Ident("color".to_string()),
+                Token::Colon,
+                Token::Whitespace,
+                Token::Ident("red".to_string()),
+                Token::Semicolon,
+                Token::CloseCurly,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_comments() {
+        assert_eq!(
+            tokenize("/**/ body/* a; } */{}"),
+            vec![
+                Token::Whitespace,
+                Token::Ident("body".to_string()),
+                Token::OpenCurly,
+                Token::CloseCurly,
+            ]
+        );
+        assert_eq!(tokenize("a /* unclosed"), tokenize("a "));
+    }
+
+    #[test]
+    fn tokenize_strings() {
+        assert_eq!(
+            tokenize(r#""a;b}" 'c\'d' "/* not a comment */""#),
+            vec![
+                Token::QuotedString("a;b}".to_string()),
+                Token::Whitespace,
+                Token::QuotedString("c'd".to_string()),
+                Token::Whitespace,
+                Token::QuotedString("/* not a comment */".to_string()),
+            ]
+        );
+        assert_eq!(
+            tokenize("\"broken\nx"),
+            vec![
+                Token::BadString,
+                Token::Whitespace,
+                Token::Ident("x".to_string())
+            ]
+        );
+        assert_eq!(
+            tokenize("\"unclosed"),
+            vec![Token::QuotedString("unclosed".to_string())]
+        );
+    }
+
+    #[test]
+    fn tokenize_escapes() {
+        assert_eq!(
+            tokenize(r".a\:b \31 0 \"),
+            vec![
+                Token::Delim('.'),
+                Token::Ident("a:b".to_string()),
+                Token::Whitespace,
+                Token::Ident("10".to_string()),
+                Token::Whitespace,
+                Token::Delim('\\'),
+            ]
+        );
+        assert_eq!(
+            tokenize("\"\\26 B\""),
+            vec![Token::QuotedString("&B".to_string())]
+        );
+    }
+
+    #[test]
+    fn tokenize_urls() {
+        assert_eq!(
+            tokenize("url( image.png ) url(\"a b.png\") url(a b)"),
+            vec![
+                Token::Url("image.png".to_string()),
+                Token::Whitespace,
+                Token::Function("url".to_string()),
+                Token::QuotedString("a b.png".to_string()),
+                Token::CloseParen,
+                Token::Whitespace,
+                Token::BadUrl,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_numbers() {
+        assert_eq!(
+            tokenize("10 -1.5 .5em +3e2px 20% 1e"),
+            vec![
+                Token::Number(10., NumberType::Integer),
+                Token::Whitespace,
+                Token::Number(-1.5, NumberType::Number),
+                Token::Whitespace,
+                Token::Dimension(0.5, NumberType::Number, "em".to_string()),
+                Token::Whitespace,
+                Token::Dimension(300., NumberType::Number, "px".to_string()),
+                Token::Whitespace,
+                Token::Percentage(20.),
+                Token::Whitespace,
+                Token::Dimension(1., NumberType::Integer, "e".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_hash_and_at_keyword() {
+        assert_eq!(
+            tokenize("#page #1a @media @ -"),
+            vec![
+                Token::Hash("page".to_string(), HashType::Id),
+                Token::Whitespace,
+                Token::Hash("1a".to_string(), HashType::Unrestricted),
+                Token::Whitespace,
+                Token::AtKeyword("media".to_string()),
+                Token::Whitespace,
+                Token::Delim('@'),
+                Token::Whitespace,
+                Token::Delim('-'),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_idents_and_functions() {
+        assert_eq!(
+            tokenize("--brand -webkit-flex rgb(0)"),
+            vec![
+                Token::Ident("--brand".to_string()),
+                Token::Whitespace,
+                Token::Ident("-webkit-flex".to_string()),
+                Token::Whitespace,
+                Token::Function("rgb".to_string()),
+                Token::Number(0., NumberType::Integer),
+                Token::CloseParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_cdo_cdc() {
+        assert_eq!(
+            tokenize("<!-- --> <"),
+            vec![
+                Token::Cdo,
+                Token::Whitespace,
+                Token::Cdc,
+                Token::Whitespace,
+                Token::Delim('<'),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_crlf() {
+        let mut tokenizer = Tokenizer::new("a\r\nb");
+
+        assert_eq!(tokenizer.next_token(), Some(Token::Ident("a".to_string())));
+        assert_eq!(tokenizer.next_token(), Some(Token::Whitespace));
+        assert_eq!((tokenizer.token_start(), tokenizer.position()), (1, 3));
+        assert_eq!(tokenizer.next_token(), Some(Token::Ident("b".to_string())));
+        assert_eq!(tokenizer.next_token(), None);
+    }
+
+    #[test]
+    fn normalize() {
+        let tests = [
+            ("RED", "red"),
+            ("1PX Solid #FFF", "1px solid #fff"),
+            ("URL(Images/A.PNG)", "url(Images/A.PNG)"),
+            ("Url(\"Images/A.PNG\")", "url(\"Images/A.PNG\")"),
+            (
+                "\"Hello\" /* Comment */ Arial",
+                "\"Hello\" /* Comment */ arial",
+            ),
+            ("VAR(--Brand, RED) Solid", "var(--Brand, RED) solid"),
+            ("Calc(VAR(--A) * 2PX)", "calc(var(--A) * 2px)"),
+            ("--Brand", "--Brand"),
+            ("#ABCDEF #Main", "#abcdef #Main"),
+            ("1E3PX", "1e3px"),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                normalize_case(input, false),
+                expected,
+                "normalize_case \"{}\"",
+                input
+            );
+        }
+
+        for (input, expected) in [
+            ("FadeIn 1S", "FadeIn 1s"),
+            ("INHERIT", "inherit"),
+            ("None", "none"),
+        ] {
+            assert_eq!(
+                normalize_case(input, true),
+                expected,
+                "normalize_case \"{}\" with custom idents",
+                input
+            );
+        }
+    }
+}
@@ -1,10 +1,38 @@
+use css_parser::analyzer::color_vision::ColorVisionReport;
+use css_parser::analyzer::contrast::ContrastReport;
+use css_parser::analyzer::important::ImportantReport;
+use css_parser::analyzer::palette::PaletteReport;
+use css_parser::analyzer::similar_colors::SimilarColorsReport;
+use css_parser::analyzer::specificity::SpecificityReport;
+use css_parser::color::Color;
 use css_parser::parser::{parse, stringify};
 use std::fs::read_to_string;
 
 fn main() {
     let file_path = "./samples/tmp.css";
-    let contents = read_to_string(&file_path).expect("Something went wrong reading the file");
+    let contents = read_to_string(file_path).expect("Something went wrong reading the file");
 
-    println!("{}", stringify(parse(&contents)));
-    // assert_eq!(contents, stringify(parse(&contents)), "stringify(parse())");
+    let (stylesheet, diagnostics) = parse(&contents);
+
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic.render(&contents));
+    }
+
+    println!("{}", SpecificityReport::new(&stylesheet, 10));
+    println!();
+    println!("{}", ImportantReport::new(file_path, &stylesheet));
+    println!();
+    println!("{}", SimilarColorsReport::new(&stylesheet, 2.));
+    println!();
+    println!(
+        "{}",
+        ContrastReport::new(&stylesheet, &Color::new("#ffffff"))
+    );
+    println!();
+    println!("{}", ColorVisionReport::new(&stylesheet, 10., 3.));
+    println!();
+    println!("{}", PaletteReport::new(&stylesheet, 2.));
+    println!();
+    println!("{}", stringify(stylesheet));
+    // assert_eq!(contents, stringify(parse(&contents).0), "stringify(parse())");
 }